@@ -1,64 +1,55 @@
 //! This module contains the code for analyzing the input Rust code and extracting the necessary information from it.
 
+pub mod config;
 pub mod crate_;
+pub mod dependency;
+pub mod diagnostic;
+mod docstring;
+pub mod doctest;
 pub mod enum_;
+pub mod error;
+pub mod feature;
+pub mod field_list;
+pub mod filter;
+pub mod fs;
 pub mod function;
+pub mod incremental;
+pub mod links;
 pub mod module;
+pub mod options;
+pub mod package;
+pub mod sections;
 pub mod struct_;
+pub mod target;
 pub mod type_;
+pub mod workspace;
 
-pub use self::crate_::analyze_crate;
+pub use self::crate_::{
+    analyze_crate, analyze_crate_in, analyze_root_module, analyze_root_module_in, analyze_source,
+};
+pub use self::incremental::ModuleCache;
+pub use self::options::AnalysisOptions;
+pub use self::package::analyze_package;
+pub use self::workspace::{analyze_workspace, analyze_workspace_in};
 
-/// Extracts the docstring from an object's attributes
-///
-/// An initial whitespace character is stripped from the start of each line.
-///
-/// :param attrs: The attributes of the object
-///
-// TODO also extract an optional docstring type from the attributes?
-pub(super) fn docstring_from_attrs(attrs: &[syn::Attribute]) -> String {
-    attrs
-        .iter()
-        .filter_map(|attr| {
-            if attr.path().is_ident("doc") {
-                match &attr.meta {
-                    syn::Meta::NameValue(value) => {
-                        if let syn::Expr::Lit(value) = &value.value {
-                            if let syn::Lit::Str(value) = &value.lit {
-                                let string = value.value();
-                                match string.strip_prefix(' ') {
-                                    Some(string) => Some(string.to_string()),
-                                    None => Some(string),
-                                }
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
-                    }
-                    _ => None,
-                }
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
+use syn::spanned::Spanned;
+
+use crate::data_model::Span;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+use self::docstring::docstring_from_attrs;
 
-    #[test]
-    fn test_docstring_from_attrs() {
-        let attrs: Vec<syn::Attribute> = vec![
-            syn::parse_quote! { #[doc = "This is a docstring"] },
-            syn::parse_quote! { #[doc = "Another docstring"] },
-            syn::parse_quote! { #[other_attr] },
-        ];
-        let result = docstring_from_attrs(&attrs);
-        assert_eq!(result, "This is a docstring\nAnother docstring");
+/// Extracts the location of an AST node, including any attributes
+///
+/// :param file: The path to the file containing the node, relative to the crate root
+/// :param ast: The AST node
+pub(super) fn span_from_ast(file: Option<&str>, ast: &impl Spanned) -> Span {
+    let span = ast.span();
+    let (start, end) = (span.start(), span.end());
+    Span {
+        file: file.map(|f| f.to_string()),
+        start_line: start.line,
+        start_column: start.column,
+        end_line: end.line,
+        end_column: end.column,
     }
 }
@@ -0,0 +1,211 @@
+//! Read the analysis configuration of a crate
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::data_model::{CrateConfig, DocFormat, Feature, Visibility};
+
+use super::{filter::PathFilter, AnalysisOptions};
+
+impl Visibility {
+    /// The name of the visibility, e.g. ``crate``
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Crate => "crate",
+            Visibility::Private => "private",
+        }
+    }
+    /// Get the visibility from its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "public" | "pub" => Some(Visibility::Public),
+            "crate" => Some(Visibility::Crate),
+            "private" => Some(Visibility::Private),
+            _ => None,
+        }
+    }
+    /// Get the visibility of an item from its AST
+    pub(super) fn from_ast(vis: &syn::Visibility) -> Self {
+        match vis {
+            syn::Visibility::Public(_) => Visibility::Public,
+            syn::Visibility::Restricted(_) => Visibility::Crate,
+            syn::Visibility::Inherited => Visibility::Private,
+        }
+    }
+}
+
+impl AnalysisOptions {
+    /// Apply the configuration of a crate to the options
+    ///
+    /// :param config: The configuration of the crate
+    /// :param features: The features of the crate,
+    ///     used to also enable those that the configured features enable
+    pub(super) fn with_config(&self, config: &CrateConfig, features: &[Feature]) -> Self {
+        Self {
+            visibility: config.visibility,
+            include_hidden: config.include_hidden,
+            features: config
+                .features
+                .as_ref()
+                .map(|enabled| enabled_features(enabled, features)),
+            include: [&self.include[..], &config.include[..]].concat(),
+            exclude: [&self.exclude[..], &config.exclude[..]].concat(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Expand a list of enabled features with those they enable in turn,
+/// with ``default`` enabling the default features
+fn enabled_features(enabled: &[String], features: &[Feature]) -> Vec<String> {
+    let mut result = vec![];
+    let mut to_enable = enabled.to_vec();
+    if enabled.iter().any(|f| f == "default") {
+        to_enable.extend(
+            features
+                .iter()
+                .filter(|f| f.default)
+                .map(|f| f.name.clone()),
+        );
+    }
+    while let Some(name) = to_enable.pop() {
+        if result.contains(&name) {
+            continue;
+        }
+        if let Some(feature) = features.iter().find(|f| f.name == name) {
+            to_enable.extend(feature.features.clone());
+        }
+        result.push(name);
+    }
+    result.sort();
+    result
+}
+
+#[derive(Debug, Default, Deserialize)]
+/// The ``[package.metadata]`` table of a ``Cargo.toml``
+pub(super) struct PackageMetadata {
+    #[serde(rename = "sphinx-rust")]
+    sphinx_rust: Option<ConfigToml>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+/// The ``[package.metadata.sphinx-rust]`` table of a ``Cargo.toml``
+struct ConfigToml {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    visibility: Option<String>,
+    features: Option<Vec<String>>,
+    doc_format: Option<String>,
+    #[serde(default)]
+    include_hidden: bool,
+}
+
+impl PackageMetadata {
+    /// Validate the configuration of the crate, with defaults for any missing fields
+    pub(super) fn config(self) -> Result<CrateConfig> {
+        let Some(config) = self.sphinx_rust else {
+            return Ok(CrateConfig::default());
+        };
+        PathFilter::new(&config.include, &config.exclude)?;
+        let visibility = match config.visibility {
+            None => Visibility::default(),
+            Some(name) => Visibility::from_name(&name).ok_or_else(|| {
+                anyhow::anyhow!(format!(
+                    "Unknown visibility `{name}`, expected one of: public, crate, private"
+                ))
+            })?,
+        };
+        let doc_format = match config.doc_format {
+            None => None,
+            Some(name) => Some(DocFormat::from_name(&name).ok_or_else(|| {
+                anyhow::anyhow!(format!(
+                    "Unknown docstring format `{name}`, expected one of: restructuredtext, markdown, rustdoc"
+                ))
+            })?),
+        };
+        Ok(CrateConfig {
+            include: config.include,
+            exclude: config.exclude,
+            visibility,
+            features: config.features,
+            doc_format,
+            include_hidden: config.include_hidden,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_config() -> Result<()> {
+        let metadata: PackageMetadata = toml::from_str(
+            r#"
+            [sphinx-rust]
+            include = ["my_crate::api::*"]
+            exclude = ["*::bindings"]
+            visibility = "crate"
+            features = ["serde"]
+            doc-format = "md"
+            include-hidden = true
+        "#,
+        )?;
+        assert_yaml_snapshot!(metadata.config()?, @r###"
+        ---
+        include:
+          - "my_crate::api::*"
+        exclude:
+          - "*::bindings"
+        visibility: crate
+        features:
+          - serde
+        doc_format: markdown
+        include_hidden: true
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_invalid() -> Result<()> {
+        let metadata: PackageMetadata = toml::from_str("[sphinx-rust]\nvisibility = \"other\"")?;
+        assert_eq!(
+            metadata.config().unwrap_err().to_string(),
+            "Unknown visibility `other`, expected one of: public, crate, private"
+        );
+        let metadata: Result<PackageMetadata, _> = toml::from_str("[sphinx-rust]\nunknown = 1");
+        assert!(metadata.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_enabled_features() {
+        let feature = |name: &str, default: bool, features: &[&str]| Feature {
+            name: name.to_string(),
+            docstring: String::new(),
+            default,
+            implicit: false,
+            features: features.iter().map(|f| f.to_string()).collect(),
+            dependencies: vec![],
+            dependency_features: vec![],
+            items: vec![],
+        };
+        let features = [
+            feature("std", true, &["alloc"]),
+            feature("alloc", true, &[]),
+            feature("serde", false, &[]),
+        ];
+        assert_eq!(
+            enabled_features(&["default".into(), "serde".into()], &features),
+            vec!["alloc", "default", "serde", "std"]
+        );
+        assert_eq!(
+            enabled_features(&["alloc".into()], &features),
+            vec!["alloc"]
+        );
+    }
+}
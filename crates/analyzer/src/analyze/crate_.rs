@@ -1,16 +1,208 @@
 //! Analyze the crate
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
 use anyhow::{Context, Result};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::data_model::{Crate, Enum, Function, Module, Struct};
+use crate::data_model::{
+    Changes, Crate, Diagnostic, DocFormat, Enum, Function, ItemKind, ItemRef, Module, Severity,
+    Span, Struct, Target, TargetKind,
+};
+
+use super::{
+    config::PackageMetadata,
+    dependency::{resolve_dependencies, DependenciesToml},
+    diagnostic::check_duplicate_items,
+    doctest::check_code_blocks,
+    error::ParseError,
+    feature::{link_gated_items, parse_features},
+    field_list::check_field_lists,
+    filter::PathFilter,
+    fs::{FileSystem, MemoryFileSystem, RealFileSystem},
+    incremental::{record_changes, record_modules, start_crate},
+    links::resolve_links,
+    module::ModuleItems,
+    sections::check_safety_sections,
+    target::{discover_targets, TargetsToml},
+    workspace::{find_workspace_fields, WorkspaceFields},
+    AnalysisOptions,
+};
+
+/// Analyze a crate, resolving the links in its docstrings and checking them
+///
+/// :param path: The path to the crate directory, containing a ``Cargo.toml``
+pub fn analyze_crate(path: &str, options: &AnalysisOptions) -> Result<AnalysisResult> {
+    analyze_crate_in(&RealFileSystem, path, options)
+}
+
+/// Analyze a crate, reading its files from the given file system
+///
+/// :param fs: The file system to read the crate from
+/// :param path: The path to the crate directory, containing a ``Cargo.toml``
+pub fn analyze_crate_in(
+    fs: &dyn FileSystem,
+    path: &str,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult> {
+    let mut result = read_crate(fs, path, options)?;
+    resolve_links(&mut result);
+    check_docstrings(&mut result);
+    record_changes(&mut result, options);
+    Ok(result)
+}
+
+/// Analyze a crate from source held in memory, rather than on disk
+///
+/// The root module is the first of ``src/lib.rs``, ``lib.rs``, ``src/main.rs`` or ``main.rs``
+/// that is given, and paths in the analysis are relative to the root of the files.
+///
+/// :param name: The name of the crate
+/// :param files: The contents of the source files, by their path, e.g. ``src/lib.rs``
+pub fn analyze_source(
+    name: &str,
+    files: &BTreeMap<String, String>,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult> {
+    let fs = MemoryFileSystem::new(files);
+    let Some(root_module) = ["src/lib.rs", "lib.rs", "src/main.rs", "main.rs"]
+        .into_iter()
+        .find(|file| fs.is_file(Path::new(file)))
+    else {
+        return Err(anyhow::anyhow!(
+            "No root module found, expected one of: src/lib.rs, lib.rs, src/main.rs, main.rs"
+        ));
+    };
+    let mut result = read_root_module(
+        &fs,
+        name,
+        Path::new(""),
+        Path::new(root_module),
+        None,
+        None,
+        options,
+    )?;
+    resolve_links(&mut result);
+    check_docstrings(&mut result);
+    record_changes(&mut result, options);
+    Ok(result)
+}
+
+/// Analyze a crate that has no ``Cargo.toml``, e.g. one built with another build system,
+/// from its root module file
+///
+/// Paths in the analysis are relative to the directory containing the root module file.
+///
+/// :param name: The name of the crate
+/// :param root_module: The path to the root module file, e.g. ``src/lib.rs``
+/// :param version: The version of the crate, or ``0.0.0`` if not given
+/// :param edition: The Rust edition of the crate
+pub fn analyze_root_module(
+    name: &str,
+    root_module: &str,
+    version: Option<&str>,
+    edition: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult> {
+    analyze_root_module_in(
+        &RealFileSystem,
+        name,
+        root_module,
+        version,
+        edition,
+        options,
+    )
+}
+
+/// Analyze a crate that has no ``Cargo.toml``, from its root module file,
+/// reading its files from the given file system
+///
+/// :param fs: The file system to read the crate from
+/// :param name: The name of the crate
+/// :param root_module: The path to the root module file, e.g. ``src/lib.rs``
+/// :param version: The version of the crate, or ``0.0.0`` if not given
+/// :param edition: The Rust edition of the crate
+pub fn analyze_root_module_in(
+    fs: &dyn FileSystem,
+    name: &str,
+    root_module: &str,
+    version: Option<&str>,
+    edition: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult> {
+    let root_module = fs
+        .canonicalize(Path::new(root_module))
+        .context(format!("Error resolving root module path: {}", root_module))?;
+    if !fs.is_file(&root_module) {
+        return Err(anyhow::anyhow!(format!(
+            "Root module is not a file: {}",
+            root_module.to_string_lossy()
+        )));
+    }
+    let path = root_module.parent().unwrap_or(Path::new(""));
+    let mut result = read_root_module(fs, name, path, &root_module, version, edition, options)?;
+    resolve_links(&mut result);
+    check_docstrings(&mut result);
+    record_changes(&mut result, options);
+    Ok(result)
+}
+
+/// Read the modules of a crate without a ``Cargo.toml``, from its root module file
+///
+/// :param path: The directory that paths in the analysis are relative to
+fn read_root_module(
+    fs: &dyn FileSystem,
+    name: &str,
+    path: &Path,
+    root_module: &Path,
+    version: Option<&str>,
+    edition: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult> {
+    let module = name.replace('-', "_");
+    let crate_ = Crate {
+        name: module.clone(),
+        version: version.unwrap_or("0.0.0").to_string(),
+        edition: edition.map(String::from),
+        targets: vec![Target {
+            name: name.to_string(),
+            kind: TargetKind::Lib,
+            file: relative_path(path, root_module),
+            module: module.clone(),
+        }],
+        ..Default::default()
+    };
+    let filter = PathFilter::new(&options.include, &options.exclude)?;
+    let mut result = AnalysisResult::new(crate_);
+    start_crate(&result, options);
+    read_module_tree(
+        fs,
+        &mut result,
+        path,
+        root_module,
+        &module,
+        options,
+        &filter,
+    )?;
+    check_duplicate_items(&mut result);
+    record_modules(options);
+    Ok(result)
+}
 
-pub fn analyze_crate(path: &str) -> Result<AnalysisResult> {
+/// Read the ``Cargo.toml`` and modules of a crate,
+/// without resolving links or checking docstrings
+pub(super) fn read_crate(
+    fs: &dyn FileSystem,
+    path: &str,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult> {
     // make the path absolute
-    // TODO we use dunce to canonicalize the path because otherwise there is issues with python's os.path.relpath on windows, but maybe we should fix this on the Python side
-    let path =
-        dunce::canonicalize(path).context(format!("Error resolving crate path: {}", path))?;
+    let path = fs
+        .canonicalize(Path::new(path))
+        .context(format!("Error resolving crate path: {}", path))?;
     // check the path is a directory
-    if !path.is_dir() {
+    if !fs.is_dir(&path) {
         return Err(anyhow::anyhow!(format!(
             "Crate path is not a directory: {}",
             path.to_string_lossy()
@@ -18,7 +210,7 @@ pub fn analyze_crate(path: &str) -> Result<AnalysisResult> {
     }
     // check if Cargo.toml exists
     let cargo_toml_path = path.join("Cargo.toml");
-    if !cargo_toml_path.exists() {
+    if !fs.exists(&cargo_toml_path) {
         return Err(anyhow::anyhow!(format!(
             "Cargo.toml does not exist in: {}",
             path.to_string_lossy()
@@ -26,120 +218,405 @@ pub fn analyze_crate(path: &str) -> Result<AnalysisResult> {
     }
 
     // read the Cargo.toml and initialize the Crate struct
-    let contents = std::fs::read_to_string(&cargo_toml_path)?;
+    let contents = fs.read_to_string(&cargo_toml_path)?;
     let cargo_toml: CargoToml = toml::from_str(&contents).context(format!(
         "Error parsing: {}",
         cargo_toml_path.to_string_lossy()
     ))?;
 
-    // check whether the crate is a library or binary
-    let (crate_name, to_root) = if let Some(lib) = cargo_toml.lib {
-        if cargo_toml.bin.is_some() {
-            return Err(anyhow::anyhow!(format!(
-                "Both lib and bin sections in: {}",
-                path.to_string_lossy()
-            )));
-        }
-        (
-            lib.name.unwrap_or(cargo_toml.package.name),
-            lib.path.unwrap_or("src/lib.rs".to_string()),
-        )
-    } else if let Some(bin) = cargo_toml.bin {
-        (
-            bin.name.unwrap_or(cargo_toml.package.name),
-            bin.path.unwrap_or("src/main.rs".to_string()),
-        )
-    } else {
+    let Some(package) = cargo_toml.package else {
         return Err(anyhow::anyhow!(format!(
-            "No lib or bin section in: {}",
-            path.to_string_lossy()
+            "No package section in: {} (use analyze_workspace for a virtual workspace)",
+            cargo_toml_path.to_string_lossy()
         )));
     };
 
-    let mut result = AnalysisResult::new(Crate {
-        name: crate_name,
-        version: cargo_toml.package.version.clone(),
-    });
+    let targets = discover_targets(fs, &path, &package, &cargo_toml.targets)?;
+    if targets.is_empty() {
+        return Err(anyhow::anyhow!(format!(
+            "No targets found in: {}",
+            path.to_string_lossy()
+        )));
+    }
+    // the crate is named after its library, or else its first target (e.g. the main binary)
+    let crate_name = targets
+        .iter()
+        .find(|target| target.kind == TargetKind::Lib)
+        .unwrap_or(&targets[0])
+        .module
+        .clone();
+    let mut crate_ = package.into_crate(fs, crate_name, &path, &cargo_toml.dependencies)?;
+    crate_.targets = targets;
+    crate_.features = parse_features(&contents, &crate_.dependencies).context(format!(
+        "Error parsing: {}",
+        cargo_toml_path.to_string_lossy()
+    ))?;
+    // the configuration of the crate overrides the options
+    let options = &options.with_config(&crate_.config, &crate_.features);
+    let filter = PathFilter::new(&options.include, &options.exclude)?;
+    let mut result = AnalysisResult::new(crate_);
+    start_crate(&result, options);
+
+    // each target is analyzed as its own tree of modules
+    for target in result.crate_.targets.clone() {
+        let root_module = path.join(&target.file);
+        if !fs.exists(&root_module) {
+            result.diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "missing-target".to_string(),
+                message: format!(
+                    "root module of {} target `{}` does not exist: {}",
+                    target.kind.as_str(),
+                    target.name,
+                    target.file
+                ),
+                file: Some("Cargo.toml".to_string()),
+                item: vec![target.module.clone()],
+                span: None,
+            });
+            continue;
+        }
+        read_module_tree(
+            fs,
+            &mut result,
+            &path,
+            &root_module,
+            &target.module,
+            options,
+            &filter,
+        )?;
+    }
+    link_gated_items(&mut result);
+    check_duplicate_items(&mut result);
+    record_modules(options);
+
+    Ok(result)
+}
+
+/// A sub-module to read, from its declaration in its parent module
+struct PendingModule {
+    /// The directory in which to look for the module file
+    parent_dir: PathBuf,
+    name: String,
+    /// The fully qualified name of the parent module
+    parent: Vec<String>,
+    /// The path to the parent module file, relative to the crate root
+    parent_file: Option<String>,
+    /// The docstring format inherited from the parent module
+    doc_format: Option<DocFormat>,
+    /// The features that the declaration is gated on
+    declared_features: Option<Vec<String>>,
+    /// The location of the declaration in the parent module file
+    span: Option<Span>,
+}
+
+impl PendingModule {
+    /// The sub-modules declared in a module, and in the modules declared inline in it
+    ///
+    /// :param dir: The directory in which to look for the sub-module files
+    fn declared_in(module: &Module, dir: &Path) -> Vec<Self> {
+        std::iter::once(module)
+            .chain(&module.inline_modules)
+            .flat_map(|declaring| {
+                // the files of modules declared in an inline module are in its directory
+                let dir = declaring.path[module.path.len()..]
+                    .iter()
+                    .fold(dir.to_path_buf(), |dir, name| dir.join(name));
+                declaring.declarations.iter().map(move |name| Self {
+                    parent_dir: dir.clone(),
+                    name: name.to_string(),
+                    parent: declaring.path.clone(),
+                    parent_file: declaring.file.clone(),
+                    doc_format: declaring.doc_format,
+                    declared_features: declaring.declaration_features.get(name).cloned(),
+                    span: declaring.declaration_spans.get(name).cloned(),
+                })
+            })
+            .collect()
+    }
+
+    /// The fully qualified name of the module
+    fn mod_path(&self) -> Vec<String> {
+        [&self.parent[..], std::slice::from_ref(&self.name)].concat()
+    }
 
-    // check existence of the root module
-    let root_module = path.join(to_root);
-    if !root_module.exists() {
-        return Ok(result);
+    /// Find the module file, ``<name>.rs`` or ``<name>/mod.rs``,
+    /// and the directory in which to look for its own sub-modules
+    fn locate(&self, fs: &dyn FileSystem) -> Option<(PathBuf, PathBuf)> {
+        let file = self.parent_dir.join(&self.name).with_extension("rs");
+        if fs.exists(&file) {
+            return Some((file, self.parent_dir.join(&self.name)));
+        }
+        let file = self.parent_dir.join(&self.name).join("mod.rs");
+        if fs.exists(&file) {
+            return Some((file, self.parent_dir.clone()));
+        }
+        None
     }
+}
 
+/// Read the root module of a target, and recursively its public sub-modules
+///
+/// The sub-modules are read and parsed in parallel, then added to the result in the order
+/// of a depth-first traversal of their declarations, so that the output is deterministic.
+/// Modules that are excluded by the filter are not read,
+/// and they and any other excluded items are added to the filtered items of the result.
+///
+/// :param path: The crate directory
+/// :param root_module: The path to the root module file
+/// :param root_name: The name of the root module
+/// :param filter: The filter of the modules and items to include
+fn read_module_tree(
+    fs: &dyn FileSystem,
+    result: &mut AnalysisResult,
+    path: &Path,
+    root_module: &Path,
+    root_name: &str,
+    options: &AnalysisOptions,
+    filter: &PathFilter,
+) -> Result<()> {
+    if !filter.reads(&[root_name.to_string()]) {
+        result.filtered.push(ItemRef {
+            path: vec![root_name.to_string()],
+            kind: ItemKind::Module,
+        });
+        return Ok(());
+    }
     // read the top-level module
-    let content = std::fs::read_to_string(&root_module)?;
+    let root_path = [root_name.to_string()];
+    let parsed = parse_module(
+        fs,
+        path,
+        root_module,
+        &root_path,
+        result
+            .crate_
+            .config
+            .doc_format
+            .or_else(|| options.doc_formats.get(root_name).copied()),
+        options,
+    );
     let (module, structs, enums, functions) =
-        Module::parse(Some(&root_module), &[&result.crate_.name], &content).context(format!(
-            "Error parsing module {}",
-            root_module.to_string_lossy()
-        ))?;
-    let mut modules_to_read = module
-        .declarations
-        .iter()
-        .map(|s| {
-            (
-                root_module.parent().unwrap().to_path_buf(),
-                s.to_string(),
-                vec![result.crate_.name.clone()],
-            )
-        })
-        .collect::<Vec<_>>();
+        module_or_stub(result, path, root_module, &root_path, parsed, options)?;
+    let mut modules_to_read = PendingModule::declared_in(&module, root_module.parent().unwrap());
+    let mut parsed_modules = parse_sub_modules(fs, path, &modules_to_read, options, filter);
 
-    result.modules.push(module);
-    result.structs.extend(structs);
-    result.enums.extend(enums);
-    result.functions.extend(functions);
-
-    // recursively find/read the public sub-modules
-    let mut read_modules = vec![];
-    while let Some((parent_dir, module_name, parent)) = modules_to_read.pop() {
-        let (module_path, submodule_dir) =
-            if parent_dir.join(&module_name).with_extension("rs").exists() {
-                (
-                    parent_dir.join(&module_name).with_extension("rs"),
-                    parent_dir.join(&module_name),
-                )
-            } else if parent_dir.join(&module_name).join("mod.rs").exists() {
-                (
-                    parent_dir.join(&module_name).join("mod.rs"),
-                    parent_dir.to_path_buf(),
-                )
-            } else {
-                // TODO warn about missing module?
-                continue;
-            };
+    result.push_items(module, structs, enums, functions, filter);
+
+    // recursively find the public sub-modules
+    let mut read_modules = HashSet::new();
+    while let Some(pending) = modules_to_read.pop() {
+        let mod_path = pending.mod_path();
+        if !filter.reads(&mod_path) {
+            result.filtered.push(ItemRef {
+                path: mod_path,
+                kind: ItemKind::Module,
+            });
+            continue;
+        }
+        let Some((module_path, submodule_dir)) = pending.locate(fs) else {
+            let expected = pending.parent_dir.join(&pending.name);
+            result.diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "missing-module".to_string(),
+                message: format!(
+                    "file not found for module `{}`, expected {} or {}",
+                    mod_path.join("::"),
+                    relative_path(path, &expected.with_extension("rs")),
+                    relative_path(path, &expected.join("mod.rs"))
+                ),
+                file: pending.parent_file,
+                item: mod_path,
+                span: pending.span,
+            });
+            continue;
+        };
 
-        if read_modules.contains(&module_path) {
+        if !read_modules.insert(module_path.clone()) {
             continue;
         }
-        read_modules.push(module_path.clone());
-
-        let content = std::fs::read_to_string(&module_path)?;
-        let path: Vec<String> = [&parent[..], &[module_name]].concat();
-        let (module, structs, enums, functions) = Module::parse(
-            Some(&module_path),
-            &path.iter().map(|s| s.as_str()).collect::<Vec<&str>>(),
-            &content,
+
+        // the module has usually been parsed already,
+        // unless its file was first found under another name
+        let parsed = parsed_modules.remove(&mod_path).unwrap_or_else(|| {
+            parse_module(
+                fs,
+                path,
+                &module_path,
+                &mod_path,
+                pending.doc_format,
+                options,
+            )
+        });
+        let (mut module, structs, enums, functions) =
+            module_or_stub(result, path, &module_path, &mod_path, parsed, options)?;
+        // the module is also gated on the features of its declaration
+        if let Some(declared_features) = pending.declared_features {
+            for feature in declared_features.into_iter().rev() {
+                if !module.features.contains(&feature) {
+                    module.features.insert(0, feature);
+                }
+            }
+        }
+        modules_to_read.extend(PendingModule::declared_in(&module, &submodule_dir));
+        result.push_items(module, structs, enums, functions, filter);
+    }
+
+    Ok(())
+}
+
+/// Find and parse declared sub-modules, and recursively their sub-modules, in parallel
+///
+/// Each level of the module tree is parsed in turn,
+/// since modules inherit the docstring format of their parent.
+///
+/// :param path: The crate directory
+/// :param declared: The sub-modules declared in the root module
+/// :return: The parsed modules, by their fully qualified name
+fn parse_sub_modules(
+    fs: &dyn FileSystem,
+    path: &Path,
+    declared: &[PendingModule],
+    options: &AnalysisOptions,
+    filter: &PathFilter,
+) -> HashMap<Vec<String>, Result<ModuleItems>> {
+    let mut parsed = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut level = declared
+        .iter()
+        .map(|pending| (pending.mod_path(), pending.doc_format, pending.locate(fs)))
+        .collect::<Vec<_>>();
+    while !level.is_empty() {
+        // each module file is only parsed once, as when the modules are added to the result
+        let to_parse = level
+            .into_iter()
+            .filter_map(|(mod_path, doc_format, located)| {
+                let (module_path, submodule_dir) = located?;
+                (filter.reads(&mod_path) && seen.insert(module_path.clone())).then_some((
+                    mod_path,
+                    doc_format,
+                    module_path,
+                    submodule_dir,
+                ))
+            })
+            .collect::<Vec<_>>();
+        let results = to_parse
+            .into_par_iter()
+            .map(|(mod_path, doc_format, module_path, submodule_dir)| {
+                let items = parse_module(fs, path, &module_path, &mod_path, doc_format, options);
+                // locate the sub-modules of the module, for the next level
+                let declared = items.as_ref().map_or(vec![], |(module, ..)| {
+                    PendingModule::declared_in(module, &submodule_dir)
+                        .into_iter()
+                        .map(|pending| (pending.mod_path(), pending.doc_format, pending.locate(fs)))
+                        .collect()
+                });
+                (mod_path, items, declared)
+            })
+            .collect::<Vec<_>>();
+        level = vec![];
+        for (mod_path, items, declared) in results {
+            parsed.insert(mod_path, items);
+            level.extend(declared);
+        }
+    }
+    parsed
+}
+
+/// Read and parse a module file, or reuse it from the cache of the options
+///
+/// :param path: The crate directory
+/// :param module_path: The path to the module file
+/// :param mod_path: The fully qualified name of the module
+/// :param doc_format: The docstring format inherited from the parent module
+fn parse_module(
+    fs: &dyn FileSystem,
+    path: &Path,
+    module_path: &Path,
+    mod_path: &[String],
+    doc_format: Option<DocFormat>,
+    options: &AnalysisOptions,
+) -> Result<ModuleItems> {
+    let parse = |content: &str| {
+        Module::parse(
+            Some(&relative_path(path, module_path)),
+            &mod_path.iter().map(|s| s.as_str()).collect::<Vec<&str>>(),
+            content,
+            doc_format,
+            options,
         )
-        .context(format!(
+    };
+    match &options.cache {
+        Some(cache) => cache.read_module(fs, module_path, mod_path, doc_format, options, parse),
+        None => parse(&fs.read_to_string(module_path)?),
+    }
+}
+
+/// Handle the result of reading and parsing a module file
+///
+/// Unless the analysis is strict, a module that cannot be read or parsed,
+/// e.g. because it uses syntax that is not yet supported, does not stop the analysis;
+/// an error diagnostic is added, and an empty module is returned in its place.
+///
+/// :param path: The crate directory
+/// :param module_path: The path to the module file
+/// :param mod_path: The fully qualified name of the module
+/// :param parsed: The result of parsing the module
+fn module_or_stub(
+    result: &mut AnalysisResult,
+    path: &Path,
+    module_path: &Path,
+    mod_path: &[String],
+    parsed: Result<ModuleItems>,
+    options: &AnalysisOptions,
+) -> Result<ModuleItems> {
+    match parsed {
+        Ok(items) => Ok(items),
+        Err(err) if options.strict => Err(err.context(format!(
             "Error parsing module {}",
             module_path.to_string_lossy()
-        ))?;
-        modules_to_read.extend(
-            module
-                .declarations
-                .iter()
-                .map(|s| (submodule_dir.clone(), s.to_string(), path.clone()))
-                .collect::<Vec<_>>(),
-        );
-        result.modules.push(module);
-        result.structs.extend(structs);
-        result.enums.extend(enums);
-        result.functions.extend(functions);
+        ))),
+        Err(err) => {
+            let file = relative_path(path, module_path);
+            // report a parse error at its location, rather than with a source excerpt
+            let (message, span) = match err.downcast_ref::<ParseError>() {
+                Some(error) => (error.message.clone(), Some(error.span.clone())),
+                None => (format!("{:#}", err), None),
+            };
+            result.diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                code: "invalid-module".to_string(),
+                message: format!(
+                    "module `{}` could not be analyzed: {}",
+                    mod_path.join("::"),
+                    message
+                ),
+                file: Some(file.clone()),
+                item: mod_path.to_vec(),
+                span,
+            });
+            Ok((Module::stub(Some(&file), mod_path), vec![], vec![], vec![]))
+        }
     }
+}
 
-    Ok(result)
+/// Run the checks on the docstrings of an analyzed crate, adding to its diagnostics
+pub(super) fn check_docstrings(result: &mut AnalysisResult) {
+    check_safety_sections(result);
+    check_field_lists(result);
+    check_code_blocks(result);
+}
+
+/// Convert a path to a string relative to the crate root, with ``/`` separators.
+///
+/// This makes the analysis output independent of the location of the crate,
+/// and of the platform it was analyzed on.
+pub(super) fn relative_path(root: &Path, path: &Path) -> String {
+    let path = path.strip_prefix(root).unwrap_or(path);
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +627,13 @@ pub struct AnalysisResult {
     pub structs: Vec<Struct>,
     pub enums: Vec<Enum>,
     pub functions: Vec<Function>,
+    /// Problems found during the analysis, which did not prevent it from completing
+    pub diagnostics: Vec<Diagnostic>,
+    /// The modules and items that were excluded from the analysis by the include/exclude filters;
+    /// the contents of excluded modules are not read
+    pub filtered: Vec<ItemRef>,
+    /// The changes since the previous analysis of the crate, if the analysis was incremental
+    pub changes: Option<Changes>,
 }
 
 impl AnalysisResult {
@@ -160,33 +644,240 @@ impl AnalysisResult {
             structs: vec![],
             enums: vec![],
             functions: vec![],
+            diagnostics: vec![],
+            filtered: vec![],
+            changes: None,
+        }
+    }
+
+    /// Add a module and its items,
+    /// recording those items that are excluded by the filter as filtered
+    fn push_items(
+        &mut self,
+        mut module: Module,
+        structs: Vec<Struct>,
+        enums: Vec<Enum>,
+        functions: Vec<Function>,
+        filter: &PathFilter,
+    ) {
+        self.diagnostics.append(&mut module.diagnostics);
+        let inline_modules = std::mem::take(&mut module.inline_modules);
+        self.modules.push(module);
+        for inline in inline_modules {
+            if filter.reads(&inline.path) {
+                self.modules.push(inline);
+            } else {
+                self.filtered.push(ItemRef {
+                    path: inline.path,
+                    kind: ItemKind::Module,
+                });
+            }
+        }
+        for struct_ in structs {
+            if filter.includes(&struct_.path) {
+                self.structs.push(struct_);
+            } else {
+                self.filtered.push(ItemRef {
+                    path: struct_.path,
+                    kind: ItemKind::Struct,
+                });
+            }
+        }
+        for enum_ in enums {
+            if filter.includes(&enum_.path) {
+                self.enums.push(enum_);
+            } else {
+                self.filtered.push(ItemRef {
+                    path: enum_.path,
+                    kind: ItemKind::Enum,
+                });
+            }
+        }
+        for function in functions {
+            if filter.includes(&function.path) {
+                self.functions.push(function);
+            } else {
+                self.filtered.push(ItemRef {
+                    path: function.path,
+                    kind: ItemKind::Function,
+                });
+            }
         }
     }
 }
 
 #[derive(Debug, Deserialize)]
-struct CargoToml {
-    package: Package,
-    bin: Option<Bin>,
-    lib: Option<Lib>,
+pub(super) struct CargoToml {
+    pub package: Option<Package>,
+    #[serde(flatten)]
+    pub targets: TargetsToml,
+    #[serde(flatten)]
+    pub dependencies: DependenciesToml,
 }
 
 #[derive(Debug, Deserialize)]
-struct Package {
-    name: String,
-    version: String,
+#[serde(rename_all = "kebab-case")]
+pub(super) struct Package {
+    pub name: String,
+    version: Option<Inheritable<String>>,
+    edition: Option<Inheritable<String>>,
+    description: Option<Inheritable<String>>,
+    license: Option<Inheritable<String>>,
+    repository: Option<Inheritable<String>>,
+    homepage: Option<Inheritable<String>>,
+    documentation: Option<Inheritable<String>>,
+    readme: Option<Inheritable<Readme>>,
+    keywords: Option<Inheritable<Vec<String>>>,
+    categories: Option<Inheritable<Vec<String>>>,
+    authors: Option<Inheritable<Vec<String>>>,
+    rust_version: Option<Inheritable<String>>,
+    /// Whether to discover targets automatically, by kind
+    pub autolib: Option<bool>,
+    pub autobins: Option<bool>,
+    pub autoexamples: Option<bool>,
+    pub autotests: Option<bool>,
+    pub autobenches: Option<bool>,
+    #[serde(default)]
+    metadata: PackageMetadata,
+}
+
+impl Package {
+    /// Whether any of the fields are inherited from the workspace
+    fn inherits(&self) -> bool {
+        [
+            &self.version,
+            &self.edition,
+            &self.description,
+            &self.license,
+            &self.repository,
+            &self.homepage,
+            &self.documentation,
+            &self.rust_version,
+        ]
+        .iter()
+        .any(|field| Inheritable::is_inherited(field))
+            || [&self.keywords, &self.categories, &self.authors]
+                .iter()
+                .any(|field| Inheritable::is_inherited(field))
+            || Inheritable::is_inherited(&self.readme)
+    }
+
+    /// Create the crate representation,
+    /// resolving any fields inherited from the workspace
+    ///
+    /// :param name: The name of the crate
+    /// :param path: The crate directory
+    /// :param dependencies: The dependency sections of the ``Cargo.toml``
+    fn into_crate(
+        self,
+        fs: &dyn FileSystem,
+        name: String,
+        path: &Path,
+        dependencies: &DependenciesToml,
+    ) -> Result<Crate> {
+        let workspace = if self.inherits() || dependencies.inherits() {
+            find_workspace_fields(fs, path)?.ok_or_else(|| {
+                anyhow::anyhow!(format!(
+                    "Fields are inherited from the workspace, but no workspace was found for: {}",
+                    path.to_string_lossy()
+                ))
+            })?
+        } else {
+            WorkspaceFields::default()
+        };
+        let inherited = workspace.package;
+        let readme = match Inheritable::resolve(self.readme, "readme", inherited.readme)? {
+            Some(Readme::Path(readme)) => Some(readme),
+            Some(Readme::Enabled(true)) => Some("README.md".to_string()),
+            Some(Readme::Enabled(false)) => None,
+            // cargo defaults to the first of these files that exists
+            None => ["README.md", "README.txt", "README"]
+                .into_iter()
+                .find(|file| fs.is_file(&path.join(file)))
+                .map(String::from),
+        };
+        Ok(Crate {
+            name,
+            // cargo defaults to 0.0.0, if the version is not specified
+            version: Inheritable::resolve(self.version, "version", inherited.version)?
+                .unwrap_or("0.0.0".to_string()),
+            edition: Inheritable::resolve(self.edition, "edition", inherited.edition)?,
+            description: Inheritable::resolve(
+                self.description,
+                "description",
+                inherited.description,
+            )?,
+            license: Inheritable::resolve(self.license, "license", inherited.license)?,
+            repository: Inheritable::resolve(self.repository, "repository", inherited.repository)?,
+            homepage: Inheritable::resolve(self.homepage, "homepage", inherited.homepage)?,
+            documentation: Inheritable::resolve(
+                self.documentation,
+                "documentation",
+                inherited.documentation,
+            )?,
+            readme,
+            keywords: Inheritable::resolve(self.keywords, "keywords", inherited.keywords)?
+                .unwrap_or_default(),
+            categories: Inheritable::resolve(self.categories, "categories", inherited.categories)?
+                .unwrap_or_default(),
+            authors: Inheritable::resolve(self.authors, "authors", inherited.authors)?
+                .unwrap_or_default(),
+            rust_version: Inheritable::resolve(
+                self.rust_version,
+                "rust-version",
+                inherited.rust_version,
+            )?,
+            targets: vec![],
+            dependencies: resolve_dependencies(dependencies, &workspace.dependencies)?,
+            features: vec![],
+            config: self.metadata.config()?,
+            source: None,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
-struct Lib {
-    name: Option<String>,
-    path: Option<String>,
+#[serde(untagged)]
+/// The ``readme`` field of a package, which is a path, or ``false`` to disable it
+pub(super) enum Readme {
+    Path(String),
+    Enabled(bool),
 }
 
 #[derive(Debug, Deserialize)]
-struct Bin {
-    name: Option<String>,
-    path: Option<String>,
+#[serde(untagged)]
+/// A package field, which may be inherited from the workspace with ``field.workspace = true``
+enum Inheritable<T> {
+    Value(T),
+    Workspace { workspace: bool },
+}
+
+impl<T> Inheritable<T> {
+    /// Whether the field is specified, and inherited from the workspace
+    fn is_inherited(field: &Option<Self>) -> bool {
+        matches!(field, Some(Inheritable::Workspace { .. }))
+    }
+
+    /// Resolve the value of a field, which may be inherited from the workspace
+    ///
+    /// :param field: The field, if specified
+    /// :param key: The key of the field, for error messages
+    /// :param inherited: The value of the field in ``[workspace.package]``
+    fn resolve(field: Option<Self>, key: &str, inherited: Option<T>) -> Result<Option<T>> {
+        match field {
+            None => Ok(None),
+            Some(Inheritable::Value(value)) => Ok(Some(value)),
+            Some(Inheritable::Workspace { workspace: false }) => Err(anyhow::anyhow!(format!(
+                "`{key}.workspace` must be true, if specified"
+            ))),
+            Some(Inheritable::Workspace { workspace: true }) => match inherited {
+                Some(value) => Ok(Some(value)),
+                None => Err(anyhow::anyhow!(format!(
+                    "`{key}` is inherited from the workspace, but is not set in [workspace.package]"
+                ))),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -257,70 +948,798 @@ mod tests {
         )?;
 
         // Analyze the dummy crate
-        let mut result = analyze_crate(temp_dir_path.to_str().unwrap())?;
-
-        // Remove the file paths for snapshot testing, as they are non-deterministic
-        for module in result.modules.iter_mut() {
-            module.file = None;
-        }
+        let result = analyze_crate(temp_dir_path.to_str().unwrap(), &AnalysisOptions::default())?;
 
         assert_yaml_snapshot!(result, @r###"
         ---
         crate_:
           name: my_crate
           version: 0.1.0
+          edition: ~
+          description: ~
+          license: ~
+          repository: ~
+          homepage: ~
+          documentation: ~
+          readme: ~
+          keywords: []
+          categories: []
+          authors: []
+          rust_version: ~
+          targets:
+            - name: my_crate
+              kind: lib
+              file: src/lib.rs
+              module: my_crate
+          dependencies: []
+          features: []
+          config:
+            include: []
+            exclude: []
+            visibility: public
+            features: ~
+            doc_format: ~
+            include_hidden: false
+          source: ~
         modules:
-          - file: ~
+          - file: src/lib.rs
             path:
               - my_crate
             docstring: The crate docstring
+            doc_format: ~
+            links: []
+            sections:
+              summary: The crate docstring
+              body: ""
+              sections: {}
+            code_blocks: []
+            features: []
             declarations:
               - my_module
-          - file: ~
+            imports: []
+          - file: src/my_module.rs
             path:
               - my_crate
               - my_module
             docstring: The module docstring
+            doc_format: ~
+            links: []
+            sections:
+              summary: The module docstring
+              body: ""
+              sections: {}
+            code_blocks: []
+            features: []
             declarations:
               - my_submodule
-          - file: ~
+            imports: []
+          - file: src/my_module/my_submodule.rs
             path:
               - my_crate
               - my_module
               - my_submodule
             docstring: The sub-module docstring
+            doc_format: ~
+            links: []
+            sections:
+              summary: The sub-module docstring
+              body: ""
+              sections: {}
+            code_blocks: []
+            features: []
             declarations: []
+            imports: []
         structs:
           - path:
               - my_crate
               - my_module
               - DummyStruct1
+            span:
+              file: src/my_module.rs
+              start_line: 4
+              start_column: 12
+              end_line: 5
+              end_column: 36
             docstring: The struct1 docstring
+            doc_format: ~
+            links: []
+            sections:
+              summary: The struct1 docstring
+              body: ""
+              sections: {}
+            code_blocks: []
+            source: "/// The struct1 docstring\npub struct DummyStruct1;"
+            features: []
             fields: []
           - path:
               - my_crate
               - my_module
               - my_submodule
               - DummyStruct2
+            span:
+              file: src/my_module/my_submodule.rs
+              start_line: 3
+              start_column: 12
+              end_line: 4
+              end_column: 36
             docstring: The struct2 docstring
+            doc_format: ~
+            links: []
+            sections:
+              summary: The struct2 docstring
+              body: ""
+              sections: {}
+            code_blocks: []
+            source: "/// The struct2 docstring\npub struct DummyStruct2;"
+            features: []
             fields: []
         enums:
           - path:
               - my_crate
               - my_module
               - DummyEnum1
+            span:
+              file: src/my_module.rs
+              start_line: 6
+              start_column: 12
+              end_line: 7
+              end_column: 34
             docstring: The enum1 docstring
+            doc_format: ~
+            links: []
+            sections:
+              summary: The enum1 docstring
+              body: ""
+              sections: {}
+            code_blocks: []
+            source: "/// The enum1 docstring\npub enum DummyEnum1 {}"
+            features: []
             variants: []
           - path:
               - my_crate
               - my_module
               - my_submodule
               - DummyEnum2
+            span:
+              file: src/my_module/my_submodule.rs
+              start_line: 5
+              start_column: 12
+              end_line: 6
+              end_column: 34
             docstring: The enum2 docstring
+            doc_format: ~
+            links: []
+            sections:
+              summary: The enum2 docstring
+              body: ""
+              sections: {}
+            code_blocks: []
+            source: "/// The enum2 docstring\npub enum DummyEnum2 {}"
+            features: []
             variants: []
         functions: []
+        diagnostics: []
+        filtered: []
+        changes: ~
+        "###);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_crate_inherited_fields() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        std::fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            r#"
+            [workspace]
+            members = ["crates/*"]
+
+            [workspace.package]
+            version = "1.2.3"
+            edition = "2021"
+            authors = ["Jane Doe"]
+            license = "MIT"
+            rust-version = "1.70"
+            readme = "README.md"
+            keywords = ["docs"]
+
+            [workspace.dependencies]
+            shared = { path = "crates/shared", features = ["base"] }
+        "#,
+        )?;
+        let crate_dir = temp_dir.path().join("crates").join("my_crate");
+        std::fs::create_dir_all(&crate_dir)?;
+        std::fs::write(
+            crate_dir.join("Cargo.toml"),
+            r#"
+            [package]
+            name = "my_crate"
+            version.workspace = true
+            edition.workspace = true
+            authors.workspace = true
+            license = "Apache-2.0"
+            rust-version = { workspace = true }
+            readme.workspace = true
+            keywords.workspace = true
+            description = "A crate"
+            homepage = "https://example.com"
+
+            [dependencies]
+            shared = { workspace = true, features = ["extra"] }
+
+            [lib]
+        "#,
+        )?;
+
+        let result = analyze_crate(crate_dir.to_str().unwrap(), &AnalysisOptions::default())?;
+        assert_yaml_snapshot!(result.crate_, @r###"
+        ---
+        name: my_crate
+        version: 1.2.3
+        edition: "2021"
+        description: A crate
+        license: Apache-2.0
+        repository: ~
+        homepage: "https://example.com"
+        documentation: ~
+        readme: "../../README.md"
+        keywords:
+          - docs
+        categories: []
+        authors:
+          - Jane Doe
+        rust_version: "1.70"
+        targets:
+          - name: my_crate
+            kind: lib
+            file: src/lib.rs
+            module: my_crate
+        dependencies:
+          - name: shared
+            package: ~
+            kind: normal
+            version: ~
+            path: "../../crates/shared"
+            git: ~
+            features:
+              - base
+              - extra
+            default_features: true
+            optional: false
+            target: ~
+        features: []
+        config:
+          include: []
+          exclude: []
+          visibility: public
+          features: ~
+          doc_format: ~
+          include_hidden: false
+        source: ~
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_crate_inherited_field_missing() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        std::fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            r#"
+            [workspace]
+
+            [workspace.package]
+            edition = "2021"
+
+            [package]
+            name = "my_crate"
+            version.workspace = true
+
+            [lib]
+        "#,
+        )?;
+
+        let err = analyze_crate(
+            temp_dir.path().to_str().unwrap(),
+            &AnalysisOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`version` is inherited from the workspace, but is not set in [workspace.package]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_crate_with_binary() -> Result<()> {
+        let fs = MemoryFileSystem::new([
+            (
+                "Cargo.toml",
+                "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"",
+            ),
+            ("src/lib.rs", "/// A struct\npub struct Shared;"),
+            (
+                "src/main.rs",
+                "//! <!-- doc-format: markdown -->\n//! The binary, using [`my_crate::Shared`]\n/// The entry point\nfn main() {}",
+            ),
+        ]);
+
+        let result = analyze_crate_in(&fs, "", &AnalysisOptions::default())?;
+        assert_yaml_snapshot!(result.crate_.targets, @r###"
+        ---
+        - name: my_crate
+          kind: lib
+          file: src/lib.rs
+          module: my_crate
+        - name: my_crate
+          kind: bin
+          file: src/main.rs
+          module: my_crate_bin
+        "###);
+        let modules = result
+            .modules
+            .iter()
+            .map(|m| (m.path_str(), m.links.clone()))
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!(modules, @r###"
+        ---
+        - - my_crate
+          - []
+        - - my_crate_bin
+          - - text: "my_crate::Shared"
+              target: "my_crate::Shared"
+              path:
+                - my_crate
+                - Shared
+              kind: struct
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_crate_with_features() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        for (file, content) in [
+            (
+                "Cargo.toml",
+                "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"\n\n[features]\n## Extra items\nextra = []",
+            ),
+            (
+                "src/lib.rs",
+                "#[cfg(feature = \"extra\")]\npub mod gated;\n#[cfg(all(unix, feature = \"extra\"))]\npub fn unix_only() {}",
+            ),
+            ("src/gated.rs", "pub struct Inner;"),
+        ] {
+            let file = temp_dir.path().join(file);
+            std::fs::create_dir_all(file.parent().unwrap())?;
+            std::fs::write(file, content)?;
+        }
+
+        let result = analyze_crate(
+            temp_dir.path().to_str().unwrap(),
+            &AnalysisOptions::default(),
+        )?;
+        assert_yaml_snapshot!(result.crate_.features, @r###"
+        ---
+        - name: extra
+          docstring: Extra items
+          default: false
+          implicit: false
+          features: []
+          dependencies: []
+          dependency_features: []
+          items:
+            - path:
+                - my_crate
+                - gated
+              kind: module
+            - path:
+                - my_crate
+                - unix_only
+              kind: function
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_crate_with_config() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        for (file, content) in [
+            (
+                "Cargo.toml",
+                r#"
+                [package]
+                name = "my_crate"
+                version = "0.1.0"
+
+                [features]
+                default = ["std"]
+                std = []
+                extra = []
+
+                [package.metadata.sphinx-rust]
+                visibility = "crate"
+                features = ["default"]
+                doc-format = "markdown"
+                "#,
+            ),
+            (
+                "src/lib.rs",
+                r#"
+                pub struct Public;
+                pub(crate) struct Internal;
+                struct Private;
+                #[doc(hidden)]
+                pub struct Hidden;
+                #[cfg(feature = "std")]
+                pub fn with_std() {}
+                #[cfg(feature = "extra")]
+                pub fn with_extra() {}
+                "#,
+            ),
+        ] {
+            let file = temp_dir.path().join(file);
+            std::fs::create_dir_all(file.parent().unwrap())?;
+            std::fs::write(file, content)?;
+        }
+
+        let result = analyze_crate(
+            temp_dir.path().to_str().unwrap(),
+            &AnalysisOptions::default(),
+        )?;
+        let items = result
+            .structs
+            .iter()
+            .map(|s| (s.path_str(), s.doc_format))
+            .chain(
+                result
+                    .functions
+                    .iter()
+                    .map(|f| (f.path_str(), f.doc_format)),
+            )
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!(items, @r###"
+        ---
+        - - "my_crate::Public"
+          - markdown
+        - - "my_crate::Internal"
+          - markdown
+        - - "my_crate::with_std"
+          - markdown
         "###);
+        Ok(())
+    }
 
+    #[test]
+    fn test_analyze_crate_with_filters() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        for (file, content) in [
+            (
+                "Cargo.toml",
+                r#"
+                [package]
+                name = "my_crate"
+                version = "0.1.0"
+
+                [package.metadata.sphinx-rust]
+                include = ["my_crate::api::*"]
+                exclude = ["*::bindings", "my_crate::api::Internal"]
+                "#,
+            ),
+            (
+                "src/lib.rs",
+                "pub mod api;\npub mod other;\npub struct Root;",
+            ),
+            (
+                "src/api.rs",
+                "pub mod bindings;\npub struct Public;\npub struct Internal;",
+            ),
+            ("src/api/bindings.rs", "this is not parsed"),
+            ("src/other.rs", "this is not parsed either"),
+        ] {
+            let file = temp_dir.path().join(file);
+            std::fs::create_dir_all(file.parent().unwrap())?;
+            std::fs::write(file, content)?;
+        }
+
+        let result = analyze_crate(
+            temp_dir.path().to_str().unwrap(),
+            &AnalysisOptions::default(),
+        )?;
+        let modules = result
+            .modules
+            .iter()
+            .map(|m| m.path_str())
+            .collect::<Vec<_>>();
+        let structs = result
+            .structs
+            .iter()
+            .map(|s| s.path_str())
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!((modules, structs), @r###"
+        ---
+        - - my_crate
+          - "my_crate::api"
+        - - "my_crate::api::Public"
+        "###);
+        assert_yaml_snapshot!(result.filtered, @r###"
+        ---
+        - path:
+            - my_crate
+            - Root
+          kind: struct
+        - path:
+            - my_crate
+            - other
+          kind: module
+        - path:
+            - my_crate
+            - api
+            - Internal
+          kind: struct
+        - path:
+            - my_crate
+            - api
+            - bindings
+          kind: module
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_root_module() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        for (file, content) in [
+            (
+                "src/lib.rs",
+                "//! <!-- doc-format: markdown -->\n//! A crate without a Cargo.toml\npub mod sub;\n/// See [`sub::Item`]\npub struct Root;",
+            ),
+            ("src/sub.rs", "pub struct Item;"),
+        ] {
+            let file = temp_dir.path().join(file);
+            std::fs::create_dir_all(file.parent().unwrap())?;
+            std::fs::write(file, content)?;
+        }
+
+        let result = analyze_root_module(
+            "my-crate",
+            temp_dir.path().join("src/lib.rs").to_str().unwrap(),
+            Some("1.2.3"),
+            Some("2021"),
+            &AnalysisOptions::default(),
+        )?;
+        let crate_ = &result.crate_;
+        assert_yaml_snapshot!((&crate_.name, &crate_.version, &crate_.targets), @r###"
+        ---
+        - my_crate
+        - 1.2.3
+        - - name: my-crate
+            kind: lib
+            file: lib.rs
+            module: my_crate
+        "###);
+        let modules = result
+            .modules
+            .iter()
+            .map(|m| (m.path_str(), m.file.clone()))
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!(modules, @r###"
+        ---
+        - - my_crate
+          - lib.rs
+        - - "my_crate::sub"
+          - sub.rs
+        "###);
+        assert_yaml_snapshot!(result.structs[0].links, @r###"
+        ---
+        - text: "sub::Item"
+          target: "sub::Item"
+          path:
+            - my_crate
+            - sub
+            - Item
+          kind: struct
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_source() -> Result<()> {
+        let files = BTreeMap::from([
+            (
+                "src/lib.rs".to_string(),
+                "pub mod sub;\n/// See [`sub::Item`]\npub struct Root;".to_string(),
+            ),
+            ("src/sub/mod.rs".to_string(), "pub struct Item;".to_string()),
+        ]);
+        // the docstrings are Markdown by default, so their links are resolved
+        let options = AnalysisOptions {
+            doc_formats: BTreeMap::from([("my_crate".to_string(), DocFormat::Markdown)]),
+            ..Default::default()
+        };
+        let result = analyze_source("my_crate", &files, &options)?;
+        let modules = result
+            .modules
+            .iter()
+            .map(|m| (m.path_str(), m.file.clone()))
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!(modules, @r###"
+        ---
+        - - my_crate
+          - src/lib.rs
+        - - "my_crate::sub"
+          - src/sub/mod.rs
+        "###);
+        assert_yaml_snapshot!(result.structs[0].links, @r###"
+        ---
+        - text: "sub::Item"
+          target: "sub::Item"
+          path:
+            - my_crate
+            - sub
+            - Item
+          kind: struct
+        "###);
+
+        let error = analyze_source("my_crate", &BTreeMap::new(), &AnalysisOptions::default());
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .starts_with("No root module found"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_inline_modules() -> Result<()> {
+        let files = BTreeMap::from([
+            (
+                "src/lib.rs".to_string(),
+                "pub mod inline {\n    //! An inline module\n    pub struct X;\n    pub mod nested {\n        pub mod file;\n    }\n}\npub mod other {}\n".to_string(),
+            ),
+            ("src/inline/nested/file.rs".to_string(), "pub fn f() {}".to_string()),
+        ]);
+        let result = analyze_source("my_crate", &files, &AnalysisOptions::default())?;
+        assert_yaml_snapshot!(result.diagnostics, @r###"
+        ---
+        []
+        "###);
+        let modules = result
+            .modules
+            .iter()
+            .map(|m| (m.path_str(), m.file.clone(), m.docstring.clone()))
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!(modules, @r###"
+        ---
+        - - my_crate
+          - src/lib.rs
+          - ""
+        - - "my_crate::inline"
+          - src/lib.rs
+          - An inline module
+        - - "my_crate::inline::nested"
+          - src/lib.rs
+          - ""
+        - - "my_crate::other"
+          - src/lib.rs
+          - ""
+        - - "my_crate::inline::nested::file"
+          - src/inline/nested/file.rs
+          - ""
+        "###);
+        let items = result
+            .structs
+            .iter()
+            .map(|s| s.path_str())
+            .chain(result.functions.iter().map(|f| f.path_str()))
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!(items, @r###"
+        ---
+        - "my_crate::inline::X"
+        - "my_crate::inline::nested::file::f"
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_invalid_module() -> Result<()> {
+        let files = BTreeMap::from([
+            (
+                "src/lib.rs".to_string(),
+                "pub mod broken;\npub mod good;".to_string(),
+            ),
+            (
+                "src/broken.rs".to_string(),
+                "/// A struct\npub struct Broken".to_string(),
+            ),
+            ("src/good.rs".to_string(), "pub struct Good;".to_string()),
+        ]);
+        let result = analyze_source("my_crate", &files, &AnalysisOptions::default())?;
+        let modules = result
+            .modules
+            .iter()
+            .map(|m| m.path_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            modules,
+            vec!["my_crate", "my_crate::good", "my_crate::broken"]
+        );
+        assert_eq!(result.structs[0].path_str(), "my_crate::good::Good");
+        assert_yaml_snapshot!(result.diagnostics, @r###"
+        ---
+        - severity: error
+          code: invalid-module
+          message: "module `my_crate::broken` could not be analyzed: unexpected end of input, expected one of: `where`, parentheses, curly braces, `;`"
+          file: src/broken.rs
+          item:
+            - my_crate
+            - broken
+          span:
+            file: src/broken.rs
+            start_line: 2
+            start_column: 17
+            end_line: 2
+            end_column: 17
+        "###);
+
+        let options = AnalysisOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let error = analyze_source("my_crate", &files, &options).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Error parsing module src/broken.rs"));
+        let parse_error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(parse_error.span.start_line, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_module_tree_order() -> Result<()> {
+        let mut files = BTreeMap::from([(
+            "src/lib.rs".to_string(),
+            "pub mod a;\npub mod b;\npub mod c;".to_string(),
+        )]);
+        for name in ["a", "b", "c"] {
+            files.insert(
+                format!("src/{name}.rs"),
+                "pub mod x;\npub mod y;".to_string(),
+            );
+            for sub in ["x", "y"] {
+                files.insert(format!("src/{name}/{sub}.rs"), String::new());
+            }
+        }
+        let result = analyze_source("my_crate", &files, &AnalysisOptions::default())?;
+        let modules = result
+            .modules
+            .iter()
+            .map(|m| m.path_str())
+            .collect::<Vec<_>>();
+        // the modules are in the order of a depth-first traversal, however they are parsed
+        assert_yaml_snapshot!(modules, @r###"
+        ---
+        - my_crate
+        - "my_crate::c"
+        - "my_crate::c::y"
+        - "my_crate::c::x"
+        - "my_crate::b"
+        - "my_crate::b::y"
+        - "my_crate::b::x"
+        - "my_crate::a"
+        - "my_crate::a::y"
+        - "my_crate::a::x"
+        "###);
+        for _ in 0..5 {
+            let other = analyze_source("my_crate", &files, &AnalysisOptions::default())?;
+            let other = other
+                .modules
+                .iter()
+                .map(|m| m.path_str())
+                .collect::<Vec<_>>();
+            assert_eq!(modules, other);
+        }
         Ok(())
     }
 }
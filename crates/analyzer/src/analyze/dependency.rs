@@ -0,0 +1,266 @@
+//! Read the dependencies of a crate
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::data_model::{Dependency, DependencyKind};
+
+impl DependencyKind {
+    /// The name of the kind, e.g. ``dev``
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "normal",
+            DependencyKind::Dev => "dev",
+            DependencyKind::Build => "build",
+        }
+    }
+}
+
+/// Read the dependencies of all kinds, including platform-specific ones,
+/// resolving those inherited from the workspace with ``dep.workspace = true``
+///
+/// :param inherited: The ``[workspace.dependencies]`` of the workspace,
+///     with paths relative to the crate directory
+pub(super) fn resolve_dependencies(
+    manifest: &DependenciesToml,
+    inherited: &BTreeMap<String, DependencyToml>,
+) -> Result<Vec<Dependency>> {
+    let mut tables = vec![(None, &manifest.tables)];
+    tables.extend(
+        manifest
+            .target
+            .iter()
+            .map(|(platform, tables)| (Some(platform), tables)),
+    );
+    let mut dependencies = vec![];
+    for (platform, tables) in tables {
+        for (kind, table) in [
+            (DependencyKind::Normal, &tables.dependencies),
+            (DependencyKind::Dev, &tables.dev_dependencies),
+            (DependencyKind::Build, &tables.build_dependencies),
+        ] {
+            for (name, dependency) in table {
+                let detail = dependency.detail();
+                let detail = if detail.workspace == Some(true) {
+                    let Some(base) = inherited.get(name) else {
+                        return Err(anyhow::anyhow!(format!(
+                            "dependency `{name}` is inherited from the workspace, but is not set in [workspace.dependencies]"
+                        )));
+                    };
+                    detail.inherit(base.detail())
+                } else {
+                    detail
+                };
+                dependencies.push(Dependency {
+                    name: name.clone(),
+                    package: detail.package,
+                    kind,
+                    version: detail.version,
+                    path: detail.path,
+                    git: detail.git,
+                    features: detail.features.unwrap_or_default(),
+                    default_features: detail.default_features.unwrap_or(true),
+                    optional: detail.optional.unwrap_or(false),
+                    target: platform.cloned(),
+                });
+            }
+        }
+    }
+    Ok(dependencies)
+}
+
+impl DependenciesToml {
+    /// Whether any of the dependencies are inherited from the workspace
+    pub(super) fn inherits(&self) -> bool {
+        [&self.tables]
+            .into_iter()
+            .chain(self.target.values())
+            .flat_map(|tables| {
+                [
+                    &tables.dependencies,
+                    &tables.dev_dependencies,
+                    &tables.build_dependencies,
+                ]
+            })
+            .flat_map(|table| table.values())
+            .any(|dependency| dependency.detail().workspace == Some(true))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+/// The dependency sections of a ``Cargo.toml``
+pub(super) struct DependenciesToml {
+    #[serde(flatten)]
+    tables: DependencyTables,
+    /// Platform-specific dependencies, e.g. ``[target.'cfg(windows)'.dependencies]``
+    #[serde(default)]
+    target: BTreeMap<String, DependencyTables>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct DependencyTables {
+    #[serde(default)]
+    dependencies: BTreeMap<String, DependencyToml>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, DependencyToml>,
+    #[serde(default)]
+    build_dependencies: BTreeMap<String, DependencyToml>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(super) enum DependencyToml {
+    /// Only a version requirement, e.g. ``serde = "1.0"``
+    Simple(String),
+    Detailed(DependencyDetail),
+}
+
+impl DependencyToml {
+    fn detail(&self) -> DependencyDetail {
+        match self {
+            DependencyToml::Simple(version) => DependencyDetail {
+                version: Some(version.clone()),
+                ..Default::default()
+            },
+            DependencyToml::Detailed(detail) => detail.clone(),
+        }
+    }
+
+    /// The path of the dependency, if it is a path dependency
+    pub(super) fn path(&self) -> Option<&str> {
+        match self {
+            DependencyToml::Simple(_) => None,
+            DependencyToml::Detailed(detail) => detail.path.as_deref(),
+        }
+    }
+
+    /// Replace the path of a path dependency
+    pub(super) fn set_path(&mut self, path: String) {
+        if let DependencyToml::Detailed(detail) = self {
+            detail.path = Some(path);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) struct DependencyDetail {
+    version: Option<String>,
+    path: Option<String>,
+    git: Option<String>,
+    package: Option<String>,
+    features: Option<Vec<String>>,
+    #[serde(alias = "default_features")]
+    default_features: Option<bool>,
+    optional: Option<bool>,
+    workspace: Option<bool>,
+}
+
+impl DependencyDetail {
+    /// Inherit a dependency from the workspace, as cargo does:
+    /// the source is taken from the workspace, features are combined,
+    /// and whether it is optional is taken from the member
+    fn inherit(self, base: DependencyDetail) -> DependencyDetail {
+        let mut features = base.features.unwrap_or_default();
+        for feature in self.features.unwrap_or_default() {
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+        DependencyDetail {
+            features: Some(features),
+            optional: self.optional,
+            workspace: None,
+            ..base
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_resolve_dependencies() -> Result<()> {
+        let manifest: DependenciesToml = toml::from_str(
+            r#"
+            [dependencies]
+            serde = { version = "1.0", features = ["derive"] }
+            anyhow = "1.0"
+            shared = { workspace = true, features = ["extra"], optional = true }
+
+            [dev-dependencies]
+            renamed = { path = "../other", package = "other", default-features = false }
+
+            [target.'cfg(windows)'.build-dependencies]
+            winres = { git = "https://github.com/mxre/winres" }
+        "#,
+        )?;
+        let inherited: BTreeMap<String, DependencyToml> = toml::from_str(
+            r#"
+            shared = { path = "../shared", version = "0.1", features = ["base"] }
+        "#,
+        )?;
+        assert_yaml_snapshot!(resolve_dependencies(&manifest, &inherited)?, @r###"
+        ---
+        - name: anyhow
+          package: ~
+          kind: normal
+          version: "1.0"
+          path: ~
+          git: ~
+          features: []
+          default_features: true
+          optional: false
+          target: ~
+        - name: serde
+          package: ~
+          kind: normal
+          version: "1.0"
+          path: ~
+          git: ~
+          features:
+            - derive
+          default_features: true
+          optional: false
+          target: ~
+        - name: shared
+          package: ~
+          kind: normal
+          version: "0.1"
+          path: "../shared"
+          git: ~
+          features:
+            - base
+            - extra
+          default_features: true
+          optional: true
+          target: ~
+        - name: renamed
+          package: other
+          kind: dev
+          version: ~
+          path: "../other"
+          git: ~
+          features: []
+          default_features: false
+          optional: false
+          target: ~
+        - name: winres
+          package: ~
+          kind: build
+          version: ~
+          path: ~
+          git: "https://github.com/mxre/winres"
+          features: []
+          default_features: true
+          optional: false
+          target: cfg(windows)
+        "###);
+        Ok(())
+    }
+}
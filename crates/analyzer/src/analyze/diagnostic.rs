@@ -0,0 +1,213 @@
+//! Diagnostics for problems found during the analysis
+use std::collections::BTreeMap;
+
+use crate::data_model::{Diagnostic, ItemKind, Severity};
+
+use super::crate_::AnalysisResult;
+
+impl Severity {
+    /// The name of the severity, e.g. ``warning``
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Add a diagnostic for each item that has the same fully qualified name as an earlier one,
+/// in the same namespace, e.g. alternative definitions gated on different features
+///
+/// Modules, structs and enums share the type namespace, and functions the value namespace.
+/// Only one of the definitions can be documented under the name.
+pub(super) fn check_duplicate_items(result: &mut AnalysisResult) {
+    let types = result
+        .modules
+        .iter()
+        .map(|m| (&m.path, ItemKind::Module, m.file.clone(), None))
+        .chain(result.structs.iter().map(|s| {
+            (
+                &s.path,
+                ItemKind::Struct,
+                s.span.file.clone(),
+                Some(&s.span),
+            )
+        }))
+        .chain(
+            result
+                .enums
+                .iter()
+                .map(|e| (&e.path, ItemKind::Enum, e.span.file.clone(), Some(&e.span))),
+        )
+        .collect::<Vec<_>>();
+    let values = result
+        .functions
+        .iter()
+        .map(|f| {
+            (
+                &f.path,
+                ItemKind::Function,
+                f.span.file.clone(),
+                Some(&f.span),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut diagnostics = vec![];
+    for namespace in [types, values] {
+        let mut seen: BTreeMap<&Vec<String>, (ItemKind, Option<String>)> = BTreeMap::new();
+        for (path, kind, file, span) in namespace {
+            let Some((first_kind, first_file)) = seen.get(path) else {
+                seen.insert(path, (kind, file));
+                continue;
+            };
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "duplicate-item".to_string(),
+                message: format!(
+                    "{} `{}` is already defined as a {}{}",
+                    kind.as_str(),
+                    path.join("::"),
+                    first_kind.as_str(),
+                    first_file
+                        .as_ref()
+                        .map(|f| format!(" in {f}"))
+                        .unwrap_or_default()
+                ),
+                file,
+                item: path.clone(),
+                span: span.cloned(),
+            });
+        }
+    }
+    result.diagnostics.extend(diagnostics);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::{analyze_source, AnalysisOptions};
+    use anyhow::Result;
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_analysis_diagnostics() -> Result<()> {
+        let files = BTreeMap::from([
+            (
+                "src/lib.rs".to_string(),
+                r#"
+pub mod other;
+pub mod missing;
+
+#[cfg(unix)]
+pub struct Handle;
+#[cfg(windows)]
+pub enum Handle {}
+
+pub struct other;
+
+#[doc = include_str!("../README.md")]
+pub fn function() {}
+
+pub struct WithField {
+    #[doc = include_str!("field.md")]
+    pub field: u8,
+}
+
+pub enum WithVariant {
+    #[doc = include_str!("variant.md")]
+    Variant,
+}
+"#
+                .to_string(),
+            ),
+            ("src/other.rs".to_string(), String::new()),
+        ]);
+        let result = analyze_source("my_crate", &files, &AnalysisOptions::default())?;
+        assert_yaml_snapshot!(result.diagnostics, @r###"
+        ---
+        - severity: warning
+          code: unreadable-doc-attribute
+          message: "`doc` attribute of `my_crate::function` cannot be read, so it is omitted from the docstring"
+          file: src/lib.rs
+          item:
+            - my_crate
+            - function
+          span:
+            file: src/lib.rs
+            start_line: 12
+            start_column: 0
+            end_line: 12
+            end_column: 37
+        - severity: warning
+          code: unreadable-doc-attribute
+          message: "`doc` attribute of `my_crate::WithField::field` cannot be read, so it is omitted from the docstring"
+          file: src/lib.rs
+          item:
+            - my_crate
+            - WithField
+            - field
+          span:
+            file: src/lib.rs
+            start_line: 16
+            start_column: 4
+            end_line: 16
+            end_column: 37
+        - severity: warning
+          code: unreadable-doc-attribute
+          message: "`doc` attribute of `my_crate::WithVariant::Variant` cannot be read, so it is omitted from the docstring"
+          file: src/lib.rs
+          item:
+            - my_crate
+            - WithVariant
+            - Variant
+          span:
+            file: src/lib.rs
+            start_line: 21
+            start_column: 4
+            end_line: 21
+            end_column: 39
+        - severity: warning
+          code: missing-module
+          message: "file not found for module `my_crate::missing`, expected src/missing.rs or src/missing/mod.rs"
+          file: src/lib.rs
+          item:
+            - my_crate
+            - missing
+          span:
+            file: src/lib.rs
+            start_line: 3
+            start_column: 0
+            end_line: 3
+            end_column: 16
+        - severity: warning
+          code: duplicate-item
+          message: "struct `my_crate::other` is already defined as a module in src/other.rs"
+          file: src/lib.rs
+          item:
+            - my_crate
+            - other
+          span:
+            file: src/lib.rs
+            start_line: 10
+            start_column: 0
+            end_line: 10
+            end_column: 17
+        - severity: warning
+          code: duplicate-item
+          message: "enum `my_crate::Handle` is already defined as a struct in src/lib.rs"
+          file: src/lib.rs
+          item:
+            - my_crate
+            - Handle
+          span:
+            file: src/lib.rs
+            start_line: 7
+            start_column: 0
+            end_line: 8
+            end_column: 18
+        "###);
+        Ok(())
+    }
+}
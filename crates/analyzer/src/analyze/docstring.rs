@@ -0,0 +1,495 @@
+//! Extract and normalize docstrings
+//!
+//! This follows the normalization that ``rustdoc`` applies to doc comments and attributes,
+//! so that the indentation of the docstring is preserved relative to its least indented line,
+//! which is important for whitespace-sensitive formats, like reStructuredText.
+use syn::{punctuated::Punctuated, Token};
+
+use crate::data_model::DocFormat;
+
+impl DocFormat {
+    /// Get the format from its name, or a common alias of it
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "restructuredtext" | "rst" | "rest" => Some(Self::Restructuredtext),
+            "markdown" | "md" | "myst" => Some(Self::Markdown),
+            "rustdoc" => Some(Self::Rustdoc),
+            _ => None,
+        }
+    }
+    /// The name of the format, which matches that of the Sphinx source parser
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Restructuredtext => "restructuredtext",
+            Self::Markdown => "markdown",
+            Self::Rustdoc => "rustdoc",
+        }
+    }
+}
+
+/// A single documentation attribute, e.g. a ``///`` comment or a ``#[doc = "..."]`` attribute
+struct DocFragment {
+    /// The content of the attribute
+    text: String,
+    /// Whether the fragment was written as a doc comment, rather than an explicit attribute
+    sugared: bool,
+}
+
+/// Extracts the docstring from an object's attributes, and its format if specified
+///
+/// Block comments have their ``*`` decoration stripped,
+/// then all fragments are unindented by their common indentation and concatenated.
+///
+/// The format can be specified by a ``#[doc(format = "...")]`` attribute,
+/// which may be wrapped in a ``#[cfg_attr(..., doc(format = "..."))]`` to avoid compiler warnings,
+/// or by a first line of the docstring, ``.. doc-format: ...`` or ``<!-- doc-format: ... -->``,
+/// which is then removed from the docstring.
+///
+/// :param attrs: The attributes of the object
+pub(super) fn docstring_from_attrs(attrs: &[syn::Attribute]) -> (String, Option<DocFormat>) {
+    let fragments = attrs.iter().filter_map(doc_fragment).collect::<Vec<_>>();
+    let docstring = unindent_fragments(&fragments);
+    let attr_format = attrs.iter().find_map(format_from_attr);
+    match strip_format_marker(&docstring) {
+        Some((marker_format, docstring)) => (docstring, attr_format.or(marker_format)),
+        None => (docstring, attr_format),
+    }
+}
+
+/// Get the ``doc`` attributes whose content cannot be read,
+/// e.g. ``#[doc = include_str!("...")]``, which are omitted from the docstring
+pub(super) fn unreadable_doc_attrs(
+    attrs: &[syn::Attribute],
+) -> impl Iterator<Item = &syn::Attribute> {
+    attrs.iter().filter(|attr| {
+        if !attr.path().is_ident("doc") {
+            return false;
+        }
+        match &attr.meta {
+            syn::Meta::NameValue(value) => !matches!(
+                value.value,
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(_),
+                    ..
+                })
+            ),
+            syn::Meta::List(list) => list
+                .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+                .is_err(),
+            syn::Meta::Path(_) => true,
+        }
+    })
+}
+
+/// Whether an item is marked with ``#[doc(hidden)]``
+pub(super) fn is_hidden(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let syn::Meta::List(list) = &attr.meta else {
+            return false;
+        };
+        list.path.is_ident("doc")
+            && list
+                .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+                .is_ok_and(|metas| metas.iter().any(|meta| meta.path().is_ident("hidden")))
+    })
+}
+
+/// Extract the docstring format from a ``doc(format = "...")`` attribute,
+/// or one nested in a ``cfg_attr``
+fn format_from_attr(attr: &syn::Attribute) -> Option<DocFormat> {
+    let syn::Meta::List(list) = &attr.meta else {
+        return None;
+    };
+    if list.path.is_ident("doc") {
+        return format_from_doc_list(list);
+    }
+    if list.path.is_ident("cfg_attr") {
+        let metas = list
+            .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+            .ok()?;
+        // the first item is the predicate
+        return metas.iter().skip(1).find_map(|meta| match meta {
+            syn::Meta::List(list) if list.path.is_ident("doc") => format_from_doc_list(list),
+            _ => None,
+        });
+    }
+    None
+}
+
+/// Extract the format from the contents of a ``doc(...)`` attribute
+fn format_from_doc_list(list: &syn::MetaList) -> Option<DocFormat> {
+    let metas = list
+        .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+        .ok()?;
+    metas.iter().find_map(|meta| match meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }),
+            ..
+        }) if path.is_ident("format") => DocFormat::from_name(&value.value()),
+        _ => None,
+    })
+}
+
+/// If the first line of the docstring is a format marker, remove it and return the format.
+///
+/// The marker is written as a comment, so that it is not rendered by either format,
+/// e.g. ``.. doc-format: rst`` or ``<!-- doc-format: markdown -->``.
+/// An unknown format name still removes the marker, but returns no format.
+fn strip_format_marker(docstring: &str) -> Option<(Option<DocFormat>, String)> {
+    let (first, rest) = docstring.split_once('\n').unwrap_or((docstring, ""));
+    let first = first.trim();
+    let name = first
+        .strip_prefix("..")
+        .or_else(|| {
+            first
+                .strip_prefix("<!--")
+                .and_then(|s| s.strip_suffix("-->"))
+        })?
+        .trim()
+        .strip_prefix("doc-format:")?;
+    Some((
+        DocFormat::from_name(name),
+        rest.trim_start_matches('\n').to_string(),
+    ))
+}
+
+/// Extract the documentation fragment from an attribute, if it is a ``doc`` attribute with a string value
+fn doc_fragment(attr: &syn::Attribute) -> Option<DocFragment> {
+    if !attr.path().is_ident("doc") {
+        return None;
+    }
+    let syn::Meta::NameValue(value) = &attr.meta else {
+        return None;
+    };
+    let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(value),
+        ..
+    }) = &value.value
+    else {
+        return None;
+    };
+    let sugared = is_sugared(attr);
+    let text = value.value();
+    let text = if sugared && text.contains('\n') {
+        beautify_block_comment(&text)
+    } else {
+        text
+    };
+    Some(DocFragment { text, sugared })
+}
+
+/// Whether the attribute was written as a doc comment.
+///
+/// When a doc comment is tokenized, all the tokens of the resulting attribute share the span of the comment,
+/// whereas the ``#`` of an explicit attribute spans a single character.
+fn is_sugared(attr: &syn::Attribute) -> bool {
+    let span = attr.pound_token.span;
+    let (start, end) = (span.start(), span.end());
+    start.line != end.line || end.column > start.column + 1
+}
+
+/// Strip the decoration from a multi-line ``/** ... */`` or ``/*! ... */`` comment.
+///
+/// This removes a leading line of only ``*``, a trailing line of only ``*``,
+/// and a ``*`` prefix if it is present, at the same column, on every line.
+fn beautify_block_comment(text: &str) -> String {
+    let mut lines = text.lines().collect::<Vec<_>>();
+
+    // vertical trim
+    if lines
+        .first()
+        .is_some_and(|line| line.chars().all(|c| c == '*'))
+    {
+        lines.remove(0);
+    }
+    if lines
+        .last()
+        .is_some_and(|line| !line.is_empty() && line.chars().all(|c| c == '*'))
+    {
+        lines.pop();
+    }
+
+    // horizontal trim
+    let mut star_column = None;
+    let mut all_stars = false;
+    for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+        let trimmed = line.trim_start_matches([' ', '\t']);
+        let column = line.len() - trimmed.len();
+        if !trimmed.starts_with('*') || star_column.is_some_and(|c| c != column) {
+            all_stars = false;
+            break;
+        }
+        star_column = Some(column);
+        all_stars = true;
+    }
+    if let (true, Some(column)) = (all_stars, star_column) {
+        for line in lines.iter_mut() {
+            if line.trim().is_empty() {
+                *line = "";
+            } else {
+                *line = &line[column + 1..];
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Remove the indentation common to all fragments, and concatenate them.
+///
+/// Doc comments are conventionally written with a space after the ``///``,
+/// which explicit ``#[doc = "..."]`` attributes do not have.
+/// So, if both are mixed, the explicit attributes are treated as having one more space of indentation.
+fn unindent_fragments(fragments: &[DocFragment]) -> String {
+    let mixed = fragments.iter().any(|f| f.sugared) && fragments.iter().any(|f| !f.sugared);
+    let add = usize::from(mixed);
+    let min_indent = fragments
+        .iter()
+        .flat_map(|fragment| {
+            fragment
+                .text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    line.chars().take_while(|c| *c == ' ' || *c == '\t').count()
+                        + if fragment.sugared { 0 } else { add }
+                })
+        })
+        .min()
+        .unwrap_or(0);
+
+    let mut lines = vec![];
+    for fragment in fragments {
+        let indent = if !fragment.sugared && min_indent > 0 {
+            min_indent - add
+        } else {
+            min_indent
+        };
+        // an empty fragment still represents a (blank) line
+        if fragment.text.is_empty() {
+            lines.push("");
+        }
+        for line in fragment.text.lines() {
+            if line.trim().is_empty() {
+                lines.push("");
+            } else {
+                lines.push(&line[indent..]);
+            }
+        }
+    }
+
+    // remove leading and trailing blank lines
+    let start = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(start, |i| i + 1);
+    lines[start..end].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse the docstring of the first item in some source code,
+    /// which (unlike ``parse_quote``) gives the tokens their real spans
+    fn item_docstring(content: &str) -> String {
+        let file = syn::parse_file(content).unwrap();
+        match &file.items[0] {
+            syn::Item::Struct(item) => docstring_from_attrs(&item.attrs).0,
+            _ => panic!("expected a struct"),
+        }
+    }
+
+    fn module_docstring(content: &str) -> String {
+        let file = syn::parse_file(content).unwrap();
+        docstring_from_attrs(&file.attrs).0
+    }
+
+    #[test]
+    fn test_docstring_from_attrs() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote! { #[doc = "This is a docstring"] },
+            syn::parse_quote! { #[doc = "Another docstring"] },
+            syn::parse_quote! { #[other_attr] },
+        ];
+        let result = docstring_from_attrs(&attrs);
+        assert_eq!(
+            result,
+            ("This is a docstring\nAnother docstring".into(), None)
+        );
+    }
+
+    #[test]
+    fn test_line_comments_keep_relative_indentation() {
+        let content = r#"
+/// A list:
+///
+/// - item
+///   continued
+///
+///     indented block
+struct A;
+"#;
+        assert_eq!(
+            item_docstring(content),
+            "A list:\n\n- item\n  continued\n\n    indented block"
+        );
+    }
+
+    #[test]
+    fn test_line_comments_without_space() {
+        let content = "///no space\n///  two spaces\nstruct A;";
+        assert_eq!(item_docstring(content), "no space\n  two spaces");
+    }
+
+    #[test]
+    fn test_block_comment_with_stars() {
+        let content = r#"
+/**
+ * A block comment
+ *
+ *     indented
+ */
+struct A;
+"#;
+        assert_eq!(item_docstring(content), "A block comment\n\n    indented");
+    }
+
+    #[test]
+    fn test_block_comment_without_stars() {
+        let content = r#"
+    /**
+        A block comment
+
+        .. note:: directive
+           content
+    */
+struct A;
+"#;
+        assert_eq!(
+            item_docstring(content),
+            "A block comment\n\n.. note:: directive\n   content"
+        );
+    }
+
+    #[test]
+    fn test_block_comment_single_line() {
+        let content = "/** A block comment */\nstruct A;";
+        assert_eq!(item_docstring(content), "A block comment ");
+    }
+
+    #[test]
+    fn test_inner_block_comment() {
+        let content = "/*!\n * Module docs\n *\n * More\n */\n";
+        assert_eq!(module_docstring(content), "Module docs\n\nMore");
+    }
+
+    #[test]
+    fn test_mixed_inner_attribute_and_comments() {
+        let content = r#"
+#![doc = "First line"]
+//! second line
+//!
+//!     indented
+#![doc = "last line"]
+"#;
+        assert_eq!(
+            module_docstring(content),
+            "First line\nsecond line\n\n    indented\nlast line"
+        );
+    }
+
+    #[test]
+    fn test_multi_line_attribute() {
+        let content = r#"
+#[doc = "
+    A multi-line attribute
+
+        indented
+"]
+struct A;
+"#;
+        assert_eq!(
+            item_docstring(content),
+            "A multi-line attribute\n\n    indented"
+        );
+    }
+
+    #[test]
+    fn test_non_literal_attributes_ignored() {
+        let content = r#"
+/// A comment
+#[doc(hidden)]
+#[doc = include_str!("file.md")]
+struct A;
+"#;
+        assert_eq!(item_docstring(content), "A comment");
+    }
+
+    #[test]
+    fn test_format_from_attribute() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote! { #[doc = "A docstring"] },
+            syn::parse_quote! { #[doc(hidden, format = "md")] },
+        ];
+        let result = docstring_from_attrs(&attrs);
+        assert_eq!(result, ("A docstring".into(), Some(DocFormat::Markdown)));
+    }
+
+    #[test]
+    fn test_format_from_cfg_attr() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote! { #![cfg_attr(sphinx, doc(format = "rst"))] },
+            syn::parse_quote! { #![doc = "A docstring"] },
+        ];
+        let result = docstring_from_attrs(&attrs);
+        assert_eq!(
+            result,
+            ("A docstring".into(), Some(DocFormat::Restructuredtext))
+        );
+    }
+
+    #[test]
+    fn test_format_from_marker() {
+        let content = "/// .. doc-format: rst\n///\n/// A docstring\nstruct A;";
+        let file = syn::parse_file(content).unwrap();
+        let syn::Item::Struct(item) = &file.items[0] else {
+            panic!("expected a struct")
+        };
+        assert_eq!(
+            docstring_from_attrs(&item.attrs),
+            ("A docstring".into(), Some(DocFormat::Restructuredtext))
+        );
+
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote! { #[doc = "<!-- doc-format: markdown -->"] },
+            syn::parse_quote! { #[doc = "A docstring"] },
+        ];
+        assert_eq!(
+            docstring_from_attrs(&attrs),
+            ("A docstring".into(), Some(DocFormat::Markdown))
+        );
+    }
+
+    #[test]
+    fn test_format_attribute_takes_precedence() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote! { #[doc = ".. doc-format: unknown"] },
+            syn::parse_quote! { #[doc = "A docstring"] },
+            syn::parse_quote! { #[doc(format = "markdown")] },
+        ];
+        assert_eq!(
+            docstring_from_attrs(&attrs),
+            ("A docstring".into(), Some(DocFormat::Markdown))
+        );
+    }
+}
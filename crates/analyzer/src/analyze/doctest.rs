@@ -0,0 +1,574 @@
+//! Extract code blocks from docstrings, check their syntax and write them out as doctests
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
+
+use crate::data_model::{CodeBlock, CodeLine, Diagnostic, DocFormat, Severity, Span};
+
+use super::crate_::AnalysisResult;
+
+/// Whether a word of a code block's info string is a rustdoc attribute,
+/// see: https://doc.rust-lang.org/rustdoc/write-documentation/documentation-tests.html#attributes
+fn is_rustdoc_attribute(word: &str) -> bool {
+    matches!(
+        word,
+        "ignore"
+            | "no_run"
+            | "should_panic"
+            | "compile_fail"
+            | "test_harness"
+            | "standalone_crate"
+            | "allow_fail"
+    ) || word.starts_with("ignore-")
+        || word.starts_with("edition")
+        // error codes, e.g. E0123
+        || (word.len() == 5 && word.starts_with('E') && word[1..].chars().all(|c| c.is_ascii_digit()))
+}
+
+impl CodeBlock {
+    /// Extract the fenced code blocks from a docstring
+    ///
+    /// Only docstrings in Markdown or rustdoc are searched, since reStructuredText,
+    /// which docstrings without a format are considered to be, does not use fenced code blocks.
+    /// Indented code blocks are also skipped, since in reStructuredText they are literal blocks.
+    pub fn extract(docstring: &str, doc_format: Option<DocFormat>) -> Vec<Self> {
+        if !matches!(doc_format, Some(DocFormat::Markdown | DocFormat::Rustdoc)) {
+            return vec![];
+        }
+        let mut blocks = vec![];
+        let mut current: Option<(Self, String)> = None;
+        for (event, range) in Parser::new(docstring).into_offset_iter() {
+            match event {
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                    let line = docstring[..range.start].matches('\n').count() + 1;
+                    current = Some((Self::new(line, &info), String::new()));
+                }
+                Event::Text(text) => {
+                    if let Some((_, content)) = current.as_mut() {
+                        content.push_str(&text);
+                    }
+                }
+                Event::End(TagEnd::CodeBlock) => {
+                    if let Some((block, content)) = current.take() {
+                        blocks.push(block.with_content(&content));
+                    }
+                }
+                _ => {}
+            }
+        }
+        blocks
+    }
+
+    /// Create an empty code block, from the info string of its opening fence
+    pub(crate) fn new(line: usize, info: &str) -> Self {
+        let mut lang = None;
+        let mut attributes = vec![];
+        for word in info
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(|word| word.trim_matches(|c| c == '{' || c == '}' || c == '.'))
+            .filter(|word| !word.is_empty())
+        {
+            if is_rustdoc_attribute(word) {
+                attributes.push(word.to_string());
+            } else if lang.is_none() {
+                lang = Some(word.to_string());
+            }
+        }
+        Self {
+            line,
+            lang: lang.unwrap_or("rust".to_string()),
+            attributes,
+            lines: vec![],
+        }
+    }
+
+    /// Set the lines of the block from its content
+    pub(crate) fn with_content(mut self, content: &str) -> Self {
+        let is_rust = self.is_rust();
+        self.lines = content
+            .lines()
+            .map(|line| CodeLine::parse(line, is_rust))
+            .collect();
+        self
+    }
+
+    /// Whether the block contains Rust code
+    pub fn is_rust(&self) -> bool {
+        self.lang == "rust"
+    }
+
+    /// Whether the block is a doctest that rustdoc would not run or compile
+    pub fn is_ignored(&self) -> bool {
+        self.attributes.iter().any(|a| a.starts_with("ignore"))
+    }
+
+    /// The source code of the block, including hidden lines
+    pub fn source(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The source code of the block, as shown in the documentation
+    pub fn visible_source(&self) -> String {
+        self.lines
+            .iter()
+            .filter(|line| !line.hidden)
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The source code of the block as a complete program, the way rustdoc compiles it
+    ///
+    /// Crate attributes, i.e. ``#![...]``, are moved to the top,
+    /// and, unless the block defines its own ``fn main``, the code is wrapped in one.
+    pub fn to_program(&self) -> String {
+        let mut program = self
+            .program_lines()
+            .into_iter()
+            .map(|(_, line)| line)
+            .collect::<Vec<_>>()
+            .join("\n");
+        program.push('\n');
+        program
+    }
+
+    /// The lines of the program from [`Self::to_program`],
+    /// with the index of the line in the block, if it is not part of the added wrapper
+    fn program_lines(&self) -> Vec<(Option<usize>, &str)> {
+        let (crate_attrs, body): (Vec<_>, Vec<_>) = self
+            .lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .enumerate()
+            .partition(|(_, line)| line.trim_start().starts_with("#!["));
+        let mut lines = vec![(None, "#![allow(unused)]")];
+        lines.extend(crate_attrs.into_iter().map(|(i, line)| (Some(i), line)));
+        // like rustdoc, only wrap blocks that do not define a top-level `fn main`
+        let source = body.iter().map(|(_, line)| *line).collect::<Vec<_>>();
+        let has_main = syn::parse_file(&source.join("\n")).is_ok_and(|file| {
+            file.items
+                .iter()
+                .any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == "main"))
+        });
+        if has_main {
+            lines.extend(body.into_iter().map(|(i, line)| (Some(i), line)));
+        } else {
+            lines.push((None, "fn main() {"));
+            lines.extend(body.into_iter().map(|(i, line)| (Some(i), line)));
+            lines.push((None, "}"));
+        }
+        lines
+    }
+
+    /// Check the syntax of the block, as a complete program
+    ///
+    /// :returns: The line of the first error, relative to the start of the block's content (0-based),
+    ///     and the error message
+    pub fn check_syntax(&self) -> Option<(usize, String)> {
+        let lines = self.program_lines();
+        let program = lines
+            .iter()
+            .map(|(_, line)| *line)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let err = syn::parse_file(&program).err()?;
+        // errors in the added wrapper, e.g. an unclosed delimiter, are reported at the last line
+        let line = lines
+            .get(err.span().start().line.saturating_sub(1))
+            .and_then(|(i, _)| *i)
+            .unwrap_or(self.lines.len().saturating_sub(1));
+        Some((line, err.to_string()))
+    }
+}
+
+impl CodeLine {
+    /// Parse a line of a code block, marking hidden lines of Rust code,
+    /// i.e. those starting with ``# `` or consisting only of ``#``.
+    ///
+    /// A leading ``##`` escapes the ``#``, and is replaced by a single ``#``.
+    fn parse(line: &str, is_rust: bool) -> Self {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if is_rust {
+            if trimmed == "#" {
+                return Self {
+                    text: String::new(),
+                    hidden: true,
+                };
+            }
+            if let Some(rest) = trimmed.strip_prefix("# ") {
+                return Self {
+                    text: format!("{indent}{rest}"),
+                    hidden: true,
+                };
+            }
+            if let Some(rest) = trimmed.strip_prefix("##") {
+                return Self {
+                    text: format!("{indent}#{rest}"),
+                    hidden: false,
+                };
+            }
+        }
+        Self {
+            text: line.to_string(),
+            hidden: false,
+        }
+    }
+}
+
+/// A code block, with the fully qualified name and location of its item
+type ItemCodeBlock<'a> = (&'a [String], Option<&'a Span>, &'a CodeBlock);
+
+/// The code blocks of all items in the analysis result
+pub fn all_code_blocks(result: &AnalysisResult) -> Vec<ItemCodeBlock<'_>> {
+    let mut blocks: Vec<ItemCodeBlock> = vec![];
+    for module in &result.modules {
+        blocks.extend(
+            module
+                .code_blocks
+                .iter()
+                .map(|b| (&module.path[..], None, b)),
+        );
+    }
+    for struct_ in &result.structs {
+        let span = Some(&struct_.span);
+        blocks.extend(
+            struct_
+                .code_blocks
+                .iter()
+                .map(|b| (&struct_.path[..], span, b)),
+        );
+        for field in &struct_.fields {
+            let span = Some(&field.span);
+            blocks.extend(field.code_blocks.iter().map(|b| (&field.path[..], span, b)));
+        }
+    }
+    for enum_ in &result.enums {
+        let span = Some(&enum_.span);
+        blocks.extend(enum_.code_blocks.iter().map(|b| (&enum_.path[..], span, b)));
+        for variant in &enum_.variants {
+            let span = Some(&variant.span);
+            blocks.extend(
+                variant
+                    .code_blocks
+                    .iter()
+                    .map(|b| (&variant.path[..], span, b)),
+            );
+            for field in &variant.fields {
+                let span = Some(&field.span);
+                blocks.extend(field.code_blocks.iter().map(|b| (&field.path[..], span, b)));
+            }
+        }
+    }
+    for function in &result.functions {
+        let span = Some(&function.span);
+        blocks.extend(
+            function
+                .code_blocks
+                .iter()
+                .map(|b| (&function.path[..], span, b)),
+        );
+    }
+    blocks
+}
+
+/// Check the syntax of the Rust code blocks of all docstrings,
+/// adding a diagnostic for each block that does not parse.
+///
+/// Blocks marked as ``ignore`` or ``compile_fail`` are skipped.
+pub fn check_code_blocks(result: &mut AnalysisResult) {
+    let mut diagnostics = vec![];
+    for (item, span, block) in all_code_blocks(result) {
+        if !block.is_rust()
+            || block.is_ignored()
+            || block.attributes.iter().any(|a| a == "compile_fail")
+        {
+            continue;
+        }
+        if let Some((line, message)) = block.check_syntax() {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "invalid-code-block".to_string(),
+                message: format!(
+                    "syntax error in code block of `{}`, at line {} of the docstring: {}",
+                    item.join("::"),
+                    block.line + 1 + line,
+                    message
+                ),
+                file: span.and_then(|span| span.file.clone()),
+                item: item.to_vec(),
+                span: span.cloned(),
+            });
+        }
+    }
+    result.diagnostics.extend(diagnostics);
+}
+
+/// Write the Rust code blocks of all docstrings out as standalone test files
+///
+/// Each file is named after the item and the line of the code block in its docstring,
+/// e.g. ``my_crate__Struct__3.rs``. Blocks marked as ``ignore`` are skipped.
+/// The doctests of the crate written by a previous call are removed first,
+/// so that those of removed items or code blocks do not remain.
+///
+/// :param result: The result of the analysis
+/// :param path: The directory to write the files to, which is created if it does not exist
+/// :returns: The paths of the files written
+pub fn write_doctests(result: &AnalysisResult, path: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(path).context(format!(
+        "Error creating doctest directory: {}",
+        path.to_string_lossy()
+    ))?;
+    let roots = result
+        .crate_
+        .targets
+        .iter()
+        .map(|target| target.module.as_str())
+        .chain(Some(result.crate_.name.as_str()))
+        .collect::<Vec<_>>();
+    let entries = std::fs::read_dir(path).context(format!(
+        "Error reading doctest directory: {}",
+        path.to_string_lossy()
+    ))?;
+    for entry in entries {
+        let file = entry?.path();
+        if file.extension().is_some_and(|ext| ext == "rs")
+            && doctest_root(&file).is_some_and(|root| roots.contains(&root.as_str()))
+        {
+            std::fs::remove_file(&file).context(format!(
+                "Error removing doctest: {}",
+                file.to_string_lossy()
+            ))?;
+        }
+    }
+    let mut written = vec![];
+    for (item, _, block) in all_code_blocks(result) {
+        if !block.is_rust() || block.is_ignored() {
+            continue;
+        }
+        let mut content = format!(
+            "// Doctest of `{}`, from line {} of its docstring\n",
+            item.join("::"),
+            block.line
+        );
+        if !block.attributes.is_empty() {
+            content.push_str(&format!("// attributes: {}\n", block.attributes.join(", ")));
+        }
+        content.push_str(&block.to_program());
+        let file = path.join(format!("{}__{}.rs", item.join("__"), block.line));
+        std::fs::write(&file, content)
+            .context(format!("Error writing doctest: {}", file.to_string_lossy()))?;
+        written.push(file);
+    }
+    Ok(written)
+}
+
+/// The root module of the item of a doctest file written by [`write_doctests`],
+/// read from its header, or ``None`` if the file is not such a doctest
+fn doctest_root(file: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(file).ok()?;
+    let item = content.lines().next()?.strip_prefix("// Doctest of `")?;
+    Some(item.split(['`', ':']).next()?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_extract_code_blocks() {
+        let docstring = r#"Summary
+
+```
+# use my_crate::Struct;
+let x = Struct::new();
+## not hidden
+#
+```
+
+```no_run, should_panic
+panic!();
+```
+
+```text
+# not hidden
+```
+"#;
+        assert_yaml_snapshot!(CodeBlock::extract(docstring, Some(DocFormat::Markdown)), @r###"
+        ---
+        - line: 3
+          lang: rust
+          attributes: []
+          lines:
+            - text: "use my_crate::Struct;"
+              hidden: true
+            - text: "let x = Struct::new();"
+              hidden: false
+            - text: "# not hidden"
+              hidden: false
+            - text: ""
+              hidden: true
+        - line: 10
+          lang: rust
+          attributes:
+            - no_run
+            - should_panic
+          lines:
+            - text: panic!();
+              hidden: false
+        - line: 14
+          lang: text
+          attributes: []
+          lines:
+            - text: "# not hidden"
+              hidden: false
+        "###);
+    }
+
+    #[test]
+    fn test_extract_code_blocks_restructuredtext() {
+        let docstring = "Summary\n\n```rust\nlet x = 1;\n```";
+        assert!(CodeBlock::extract(docstring, Some(DocFormat::Restructuredtext)).is_empty());
+        assert!(CodeBlock::extract(docstring, None).is_empty());
+        assert_eq!(
+            CodeBlock::extract(docstring, Some(DocFormat::Markdown)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_to_program() {
+        let blocks = CodeBlock::extract(
+            "```\n#![feature(test)]\n# use std::fmt;\nlet x = 1;\n```\n\n```\nfn main() {}\n```",
+            Some(DocFormat::Markdown),
+        );
+        assert_eq!(
+            blocks[0].to_program(),
+            "#![allow(unused)]\n#![feature(test)]\nfn main() {\nuse std::fmt;\nlet x = 1;\n}\n"
+        );
+        assert_eq!(blocks[0].visible_source(), "#![feature(test)]\nlet x = 1;");
+        assert_eq!(blocks[1].to_program(), "#![allow(unused)]\nfn main() {}\n");
+
+        // only a top-level `fn main` is not wrapped
+        let blocks = CodeBlock::extract(
+            "```\nlet s = \"fn main\";\n```\n\n```\nuse std::fmt;\n\nfn main() {}\n```",
+            Some(DocFormat::Markdown),
+        );
+        assert_eq!(
+            blocks[0].to_program(),
+            "#![allow(unused)]\nfn main() {\nlet s = \"fn main\";\n}\n"
+        );
+        assert_eq!(
+            blocks[1].to_program(),
+            "#![allow(unused)]\nuse std::fmt;\n\nfn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_doctests() -> Result<()> {
+        let mut result = AnalysisResult::new(crate::data_model::Crate {
+            name: "my_crate".into(),
+            version: "0.1.0".into(),
+            ..Default::default()
+        });
+        result.functions.push(crate::data_model::Function::parse(
+            None,
+            &["my_crate"],
+            Some(DocFormat::Markdown),
+            &syn::parse_quote! {
+                /// ```no_run
+                /// my_crate::func();
+                /// ```
+                ///
+                /// ```ignore
+                /// not compiled
+                /// ```
+                pub fn func() {}
+            },
+        ));
+        let temp_dir = tempfile::tempdir()?;
+        // a doctest of the crate from a previous run, and files it does not own
+        for (name, content) in [
+            (
+                "my_crate__removed__3.rs",
+                "// Doctest of `my_crate::removed`, from line 3",
+            ),
+            (
+                "other__func__1.rs",
+                "// Doctest of `other::func`, from line 1",
+            ),
+            ("notes.rs", "// notes"),
+        ] {
+            std::fs::write(temp_dir.path().join(name), content)?;
+        }
+        let written = write_doctests(&result, temp_dir.path())?;
+        assert_eq!(written, vec![temp_dir.path().join("my_crate__func__1.rs")]);
+        let mut files = std::fs::read_dir(temp_dir.path())?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        files.sort();
+        assert_eq!(
+            files,
+            vec!["my_crate__func__1.rs", "notes.rs", "other__func__1.rs"]
+        );
+        assert_yaml_snapshot!(std::fs::read_to_string(&written[0])?, @r###"
+        ---
+        "// Doctest of `my_crate::func`, from line 1 of its docstring\n// attributes: no_run\n#![allow(unused)]\nfn main() {\nmy_crate::func();\n}\n"
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_code_blocks() {
+        let mut result = AnalysisResult::new(crate::data_model::Crate {
+            name: "my_crate".into(),
+            version: "0.1.0".into(),
+            ..Default::default()
+        });
+        result.functions.push(crate::data_model::Function::parse(
+            None,
+            &["my_crate"],
+            Some(DocFormat::Markdown),
+            &syn::parse_quote! {
+                /// Summary
+                ///
+                /// ```
+                /// # fn main() {
+                /// let x = 1;
+                /// let y = ;
+                /// # }
+                /// ```
+                ///
+                /// ```
+                /// let x = (1;
+                /// ```
+                ///
+                /// ```compile_fail
+                /// let x = ;
+                /// ```
+                ///
+                /// ```text
+                /// not rust
+                /// ```
+                pub fn func() {}
+            },
+        ));
+        check_code_blocks(&mut result);
+        let messages = result
+            .diagnostics
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!(messages, @r###"
+        ---
+        - "syntax error in code block of `my_crate::func`, at line 6 of the docstring: expected an expression"
+        - "syntax error in code block of `my_crate::func`, at line 11 of the docstring: cannot parse string into token stream"
+        "###);
+    }
+}
@@ -2,9 +2,12 @@
 use quote::quote;
 use syn::ItemEnum;
 
-use crate::data_model::{Enum, Field, Variant};
+use crate::data_model::{CodeBlock, DocFormat, DocSections, Enum, Field, Variant, Visibility};
 
-use super::docstring_from_attrs;
+use super::{
+    docstring::is_hidden, docstring_from_attrs, feature::cfg_features, span_from_ast,
+    AnalysisOptions,
+};
 
 impl Enum {
     /// Fully qualified name of the variant
@@ -12,18 +15,37 @@ impl Enum {
         self.path.join("::")
     }
     /// Extract the relevant information from the AST
-    pub fn parse(parent: &[&str], ast: &ItemEnum) -> Self {
+    ///
+    /// :param doc_format: The docstring format inherited from the parent module
+    /// :param options: The options selecting the fields of variants to include
+    pub fn parse(
+        file: Option<&str>,
+        parent: &[&str],
+        doc_format: Option<DocFormat>,
+        ast: &ItemEnum,
+        options: &AnalysisOptions,
+    ) -> Self {
         let name = ast.ident.to_string();
         let path: Vec<&str> = parent.iter().copied().chain(Some(name.as_str())).collect();
-        let docstring = docstring_from_attrs(&ast.attrs);
+        let (docstring, own_format) = docstring_from_attrs(&ast.attrs);
+        let sections = DocSections::parse(&docstring);
+        let doc_format = own_format.or(doc_format);
+        let code_blocks = CodeBlock::extract(&docstring, doc_format);
         let variants = ast
             .variants
             .iter()
-            .map(|v| Variant::parse(&path, v))
+            .map(|v| Variant::parse(file, &path, doc_format, v, options))
             .collect::<Vec<_>>();
         Self {
             path: path.iter().map(|s| s.to_string()).collect(),
+            span: span_from_ast(file, ast),
             docstring,
+            doc_format,
+            links: vec![],
+            sections,
+            code_blocks,
+            source: None,
+            features: cfg_features(&ast.attrs),
             variants,
         }
     }
@@ -35,14 +57,26 @@ impl Variant {
         self.path.join("::")
     }
     /// Extract the relevant information from the AST
-    pub fn parse(parent: &[&str], ast: &syn::Variant) -> Self {
+    ///
+    /// :param doc_format: The docstring format inherited from the parent enum
+    /// :param options: The options selecting the fields to include
+    pub fn parse(
+        file: Option<&str>,
+        parent: &[&str],
+        doc_format: Option<DocFormat>,
+        ast: &syn::Variant,
+        options: &AnalysisOptions,
+    ) -> Self {
         let name = ast.ident.to_string();
         let path = parent
             .iter()
             .copied()
             .chain(Some(name.as_str()))
             .collect::<Vec<&str>>();
-        let docstring = docstring_from_attrs(&ast.attrs);
+        let (docstring, own_format) = docstring_from_attrs(&ast.attrs);
+        let sections = DocSections::parse(&docstring);
+        let doc_format = own_format.or(doc_format);
+        let code_blocks = CodeBlock::extract(&docstring, doc_format);
         let discriminant = ast
             .discriminant
             .as_ref()
@@ -51,11 +85,24 @@ impl Variant {
             .fields
             .iter()
             .enumerate()
-            .map(|(i, f)| Field::parse(&path, i, f))
+            // fields of variants have the visibility of their enum
+            .filter(|(_, f)| {
+                options.includes(
+                    Visibility::Public,
+                    is_hidden(&f.attrs),
+                    &cfg_features(&f.attrs),
+                )
+            })
+            .map(|(i, f)| Field::parse(file, &path, doc_format, i, f))
             .collect::<Vec<_>>();
         Self {
             path: path.iter().map(|s| s.to_string()).collect(),
+            span: span_from_ast(file, ast),
             docstring,
+            doc_format,
+            links: vec![],
+            sections,
+            code_blocks,
             discriminant,
             fields,
         }
@@ -87,33 +134,87 @@ mod tests {
                 },
             }
         };
-        let enum_ = Enum::parse(&["crate"], &ast);
+        let enum_ = Enum::parse(None, &["crate"], None, &ast, &AnalysisOptions::default());
         assert_yaml_snapshot!(enum_, @r###"
         ---
         path:
           - crate
           - MyEnum
+        span:
+          file: ~
+          start_line: 1
+          start_column: 0
+          end_line: 1
+          end_column: 0
         docstring: "Multi-line\ndocstring"
+        doc_format: ~
+        links: []
+        sections:
+          summary: Multi-line docstring
+          body: ""
+          sections: {}
+        code_blocks: []
+        source: ~
+        features: []
         variants:
           - path:
               - crate
               - MyEnum
               - MyVariant1
+            span:
+              file: ~
+              start_line: 1
+              start_column: 0
+              end_line: 1
+              end_column: 0
             docstring: variant without fields
+            doc_format: ~
+            links: []
+            sections:
+              summary: variant without fields
+              body: ""
+              sections: {}
+            code_blocks: []
             discriminant: ~
             fields: []
           - path:
               - crate
               - MyEnum
               - MyVariant2
+            span:
+              file: ~
+              start_line: 1
+              start_column: 0
+              end_line: 1
+              end_column: 0
             docstring: variant with discriminant
+            doc_format: ~
+            links: []
+            sections:
+              summary: variant with discriminant
+              body: ""
+              sections: {}
+            code_blocks: []
             discriminant: "1"
             fields: []
           - path:
               - crate
               - MyEnum
               - MyVariant3
+            span:
+              file: ~
+              start_line: 1
+              start_column: 0
+              end_line: 1
+              end_column: 0
             docstring: variant with unnamed fields
+            doc_format: ~
+            links: []
+            sections:
+              summary: variant with unnamed fields
+              body: ""
+              sections: {}
+            code_blocks: []
             discriminant: ~
             fields:
               - path:
@@ -121,14 +222,40 @@ mod tests {
                   - MyEnum
                   - MyVariant3
                   - "0"
+                span:
+                  file: ~
+                  start_line: 1
+                  start_column: 0
+                  end_line: 1
+                  end_column: 0
                 docstring: ""
+                doc_format: ~
+                links: []
+                sections:
+                  summary: ""
+                  body: ""
+                  sections: {}
+                code_blocks: []
                 type_:
                   - Path: u8
           - path:
               - crate
               - MyEnum
               - MyVariant3
+            span:
+              file: ~
+              start_line: 1
+              start_column: 0
+              end_line: 1
+              end_column: 0
             docstring: variant with named fields
+            doc_format: ~
+            links: []
+            sections:
+              summary: variant with named fields
+              body: ""
+              sections: {}
+            code_blocks: []
             discriminant: ~
             fields:
               - path:
@@ -136,9 +263,51 @@ mod tests {
                   - MyEnum
                   - MyVariant3
                   - field
+                span:
+                  file: ~
+                  start_line: 1
+                  start_column: 0
+                  end_line: 1
+                  end_column: 0
                 docstring: field docstring
+                doc_format: ~
+                links: []
+                sections:
+                  summary: field docstring
+                  body: ""
+                  sections: {}
+                code_blocks: []
                 type_:
                   - Path: u8
         "###);
     }
+
+    #[test]
+    fn test_parse_variant_fields_visibility() {
+        let ast: ItemEnum = parse_quote! {
+            pub enum MyEnum {
+                MyVariant {
+                    a: u8,
+                    #[doc(hidden)]
+                    b: u8,
+                    #[cfg(feature = "other")]
+                    c: u8,
+                },
+            }
+        };
+        let field_names = |options: &AnalysisOptions| {
+            Enum::parse(None, &["crate"], None, &ast, options).variants[0]
+                .fields
+                .iter()
+                .map(|field| field.path.last().unwrap().clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(field_names(&AnalysisOptions::default()), vec!["a", "c"]);
+        let options = AnalysisOptions {
+            include_hidden: true,
+            features: Some(vec![]),
+            ..Default::default()
+        };
+        assert_eq!(field_names(&options), vec!["a", "b"]);
+    }
 }
@@ -0,0 +1,137 @@
+//! Errors of the analysis, with their location in the source code
+use std::fmt;
+
+use crate::data_model::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An error parsing a Rust source file
+///
+/// It is displayed with an excerpt of the source code, in the style of ``rustc``:
+///
+/// .. code-block:: text
+///
+///     unexpected end of input, expected one of: `where`, parentheses, curly braces, `;`
+///      --> src/lib.rs:2:18
+///       |
+///     2 | pub struct Broken
+///       |                  ^
+pub struct ParseError {
+    /// The description of the error, e.g. ``expected `;` ``
+    pub message: String,
+    /// The location of the error, including the file, relative to the crate root
+    pub span: Span,
+    /// The line of the source code that the error starts on
+    pub source_line: String,
+}
+
+impl ParseError {
+    /// Create the error from that of ``syn``, locating it in the source code
+    ///
+    /// :param file: The path to the file, relative to the crate root
+    /// :param content: The content of the file
+    /// :param error: The error from ``syn``
+    pub fn new(file: Option<&str>, content: &str, error: &syn::Error) -> Self {
+        let (start, end) = (error.span().start(), error.span().end());
+        let mut span = Span {
+            file: file.map(|f| f.to_string()),
+            start_line: start.line,
+            start_column: start.column,
+            end_line: end.line,
+            end_column: end.column,
+        };
+        let message = error.to_string();
+        if span.start_line == 0 || message.starts_with("unexpected end of input") {
+            // the span of an error at the end of the input is that of the whole input,
+            // so place it after the last character instead
+            let last = content.trim_end().lines().count().max(1);
+            let column = content
+                .trim_end()
+                .lines()
+                .last()
+                .unwrap_or("")
+                .chars()
+                .count();
+            (span.start_line, span.start_column) = (last, column);
+            (span.end_line, span.end_column) = (last, column);
+        }
+        let source_line = content
+            .lines()
+            .nth(span.start_line - 1)
+            .unwrap_or_default()
+            .to_string();
+        Self {
+            message,
+            span,
+            source_line,
+        }
+    }
+
+    /// Render the location of the error, with an excerpt of the source code
+    /// and a caret under the error
+    pub fn excerpt(&self) -> String {
+        let line_number = self.span.start_line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        // keep tabs, so that the caret is aligned with the source line
+        let indent = self
+            .source_line
+            .chars()
+            .take(self.span.start_column)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let width = if self.span.end_line == self.span.start_line {
+            self.span.end_column.saturating_sub(self.span.start_column)
+        } else {
+            self.source_line
+                .chars()
+                .count()
+                .saturating_sub(self.span.start_column)
+        };
+        format!(
+            "{gutter}--> {}:{}:{}\n{gutter} |\n{line_number} | {}\n{gutter} | {indent}{}",
+            self.span.file.as_deref().unwrap_or("<unknown>"),
+            self.span.start_line,
+            self.span.start_column + 1,
+            self.source_line,
+            "^".repeat(width.max(1)),
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}", self.message, self.excerpt())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error() {
+        let content = "pub mod a;\n\npub fn f() -> {}\n";
+        let error = syn::parse_file(content).unwrap_err();
+        let error = ParseError::new(Some("src/lib.rs"), content, &error);
+        assert_eq!(
+            error.to_string(),
+            "expected one of: `for`, parentheses, `fn`, `unsafe`, `extern`, identifier, `::`, `<`, `dyn`, square brackets, `*`, `&`, `!`, `impl`, `_`, lifetime
+ --> src/lib.rs:3:15
+  |
+3 | pub fn f() -> {}
+  |               ^"
+        );
+
+        let content = "/// A struct\npub struct Broken";
+        let error = syn::parse_file(content).unwrap_err();
+        let error = ParseError::new(Some("src/lib.rs"), content, &error);
+        assert_eq!(
+            error.excerpt(),
+            " --> src/lib.rs:2:18
+  |
+2 | pub struct Broken
+  |                  ^"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! Analyze the features of a crate, and the items gated on them
+use anyhow::{Context, Result};
+use syn::punctuated::Punctuated;
+
+use crate::data_model::{Dependency, Feature, ItemKind, ItemRef};
+
+use super::crate_::AnalysisResult;
+
+/// Parse the ``[features]`` table of a ``Cargo.toml``
+///
+/// Features are documented following the ``document-features`` convention,
+/// with ``## `` comment lines directly above the feature.
+/// Optional dependencies that are not referred to with ``dep:`` are added as implicit features.
+///
+/// :param contents: The contents of the ``Cargo.toml``
+/// :param dependencies: The dependencies of the crate
+pub(super) fn parse_features(contents: &str, dependencies: &[Dependency]) -> Result<Vec<Feature>> {
+    let document = contents
+        .parse::<toml_edit::DocumentMut>()
+        .context("Error parsing features")?;
+    let optional = dependencies
+        .iter()
+        .filter(|dependency| dependency.optional)
+        .map(|dependency| dependency.name.as_str())
+        .collect::<Vec<_>>();
+
+    let mut features = vec![];
+    let mut defaults = vec![];
+    let mut explicit_deps = vec![];
+    if let Some(table) = document.get("features").and_then(|item| item.as_table()) {
+        for (name, item) in table.iter() {
+            let entries = item
+                .as_array()
+                .map(|array| {
+                    array
+                        .iter()
+                        .filter_map(|value| value.as_str().map(String::from))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if name == "default" {
+                defaults = entries;
+                continue;
+            }
+            let docstring = table
+                .key(name)
+                .and_then(|key| key.leaf_decor().prefix())
+                .and_then(|prefix| prefix.as_str())
+                .map(doc_comment)
+                .unwrap_or_default();
+            let mut feature = Feature {
+                name: name.to_string(),
+                docstring,
+                default: false,
+                implicit: false,
+                features: vec![],
+                dependencies: vec![],
+                dependency_features: vec![],
+                items: vec![],
+            };
+            for entry in entries {
+                if let Some(dependency) = entry.strip_prefix("dep:") {
+                    explicit_deps.push(dependency.to_string());
+                    feature.dependencies.push(dependency.to_string());
+                } else if let Some((dependency, _)) = entry.split_once('/') {
+                    // ``dep/feature`` also enables an optional dependency, but ``dep?/feature`` does not
+                    if optional.contains(&dependency) {
+                        feature.dependencies.push(dependency.to_string());
+                    }
+                    feature.dependency_features.push(entry);
+                } else if table.contains_key(&entry) {
+                    feature.features.push(entry);
+                } else {
+                    feature.dependencies.push(entry);
+                }
+            }
+            features.push(feature);
+        }
+    }
+
+    // optional dependencies define an implicit feature, unless they are referred to with ``dep:``
+    for dependency in optional {
+        if !explicit_deps.iter().any(|d| d == dependency)
+            && !features.iter().any(|f| f.name == dependency)
+        {
+            features.push(Feature {
+                name: dependency.to_string(),
+                docstring: String::new(),
+                default: false,
+                implicit: true,
+                features: vec![],
+                dependencies: vec![dependency.to_string()],
+                dependency_features: vec![],
+                items: vec![],
+            });
+        }
+    }
+
+    // mark the features that are enabled by default, directly or transitively
+    let mut to_enable = defaults;
+    while let Some(name) = to_enable.pop() {
+        if let Some(feature) = features.iter_mut().find(|f| f.name == name && !f.default) {
+            feature.default = true;
+            to_enable.extend(feature.features.clone());
+        }
+    }
+
+    Ok(features)
+}
+
+/// Extract the documentation from the comments above a feature, i.e. lines starting with ``##``
+fn doc_comment(prefix: &str) -> String {
+    prefix
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("##"))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Get the features that an item is gated on, from its ``#[cfg(...)]`` attributes
+///
+/// Features required within ``all(...)`` and ``any(...)`` are included,
+/// but not those within ``not(...)``.
+pub(super) fn cfg_features(attrs: &[syn::Attribute]) -> Vec<String> {
+    let mut features = vec![];
+    for attr in attrs {
+        if !attr.path().is_ident("cfg") {
+            continue;
+        }
+        if let Ok(meta) = attr.parse_args::<syn::Meta>() {
+            collect_features(&meta, &mut features);
+        }
+    }
+    features
+}
+
+fn collect_features(meta: &syn::Meta, features: &mut Vec<String>) {
+    match meta {
+        syn::Meta::NameValue(name_value) if name_value.path.is_ident("feature") => {
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(name),
+                ..
+            }) = &name_value.value
+            {
+                if !features.contains(&name.value()) {
+                    features.push(name.value());
+                }
+            }
+        }
+        syn::Meta::List(list) if list.path.is_ident("all") || list.path.is_ident("any") => {
+            if let Ok(nested) =
+                list.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+            {
+                for meta in &nested {
+                    collect_features(meta, features);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Add the items gated on each feature of the crate to the feature
+pub(super) fn link_gated_items(result: &mut AnalysisResult) {
+    let gated = result
+        .modules
+        .iter()
+        .map(|m| (&m.features, &m.path, ItemKind::Module))
+        .chain(
+            result
+                .structs
+                .iter()
+                .map(|s| (&s.features, &s.path, ItemKind::Struct)),
+        )
+        .chain(
+            result
+                .enums
+                .iter()
+                .map(|e| (&e.features, &e.path, ItemKind::Enum)),
+        )
+        .chain(
+            result
+                .functions
+                .iter()
+                .map(|f| (&f.features, &f.path, ItemKind::Function)),
+        );
+    for (features, path, kind) in gated {
+        for name in features {
+            if let Some(feature) = result.crate_.features.iter_mut().find(|f| &f.name == name) {
+                feature.items.push(ItemRef {
+                    path: path.clone(),
+                    kind,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_model::DependencyKind;
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_parse_features() -> Result<()> {
+        let contents = r#"
+[features]
+default = ["std"]
+## Use the standard library
+std = ["alloc"]
+## Use the ``alloc`` crate
+##
+## Implied by ``std``
+alloc = []
+# not documentation
+serde = ["dep:serde", "time?/serde"]
+"#;
+        let optional = |name: &str| Dependency {
+            name: name.to_string(),
+            package: None,
+            kind: DependencyKind::Normal,
+            version: Some("1".to_string()),
+            path: None,
+            git: None,
+            features: vec![],
+            default_features: true,
+            optional: true,
+            target: None,
+        };
+        let features = parse_features(contents, &[optional("serde"), optional("time")])?;
+        assert_yaml_snapshot!(features, @r###"
+        ---
+        - name: std
+          docstring: Use the standard library
+          default: true
+          implicit: false
+          features:
+            - alloc
+          dependencies: []
+          dependency_features: []
+          items: []
+        - name: alloc
+          docstring: "Use the ``alloc`` crate\n\nImplied by ``std``"
+          default: true
+          implicit: false
+          features: []
+          dependencies: []
+          dependency_features: []
+          items: []
+        - name: serde
+          docstring: ""
+          default: false
+          implicit: false
+          features: []
+          dependencies:
+            - serde
+          dependency_features:
+            - time?/serde
+          items: []
+        - name: time
+          docstring: ""
+          default: false
+          implicit: true
+          features: []
+          dependencies:
+            - time
+          dependency_features: []
+          items: []
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cfg_features() {
+        let item: syn::ItemFn = syn::parse_quote! {
+            #[cfg(feature = "a")]
+            #[cfg(all(feature = "b", any(unix, feature = "c"), not(feature = "d")))]
+            #[cfg_attr(docsrs, doc(cfg(feature = "a")))]
+            pub fn gated() {}
+        };
+        assert_eq!(cfg_features(&item.attrs), vec!["a", "b", "c"]);
+    }
+}
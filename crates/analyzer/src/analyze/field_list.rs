@@ -0,0 +1,196 @@
+//! Check docstring field lists against function signatures
+use crate::data_model::{Diagnostic, Function, Severity, TypeSegment};
+
+use super::crate_::AnalysisResult;
+
+/// A field of a reST field list, e.g. ``:param name: description``
+#[derive(Debug, PartialEq)]
+struct DocField<'a> {
+    /// The kind of field, e.g. ``param``
+    kind: &'a str,
+    /// The argument of the field, e.g. the parameter name
+    argument: Option<&'a str>,
+}
+
+/// Extract the fields of the field lists in a docstring
+///
+/// Only the last word of the field name is taken as its argument,
+/// so that typed fields, such as ``:param int x:``, are also supported.
+fn parse_fields(docstring: &str) -> Vec<DocField<'_>> {
+    docstring
+        .lines()
+        .filter_map(|line| {
+            let (name, _) = line.strip_prefix(':')?.split_once(':')?;
+            let mut words = name.split_whitespace();
+            let kind = words.next()?;
+            Some(DocField {
+                kind,
+                argument: words.last(),
+            })
+        })
+        .collect()
+}
+
+/// Whether the function returns a ``Result``, e.g. ``Result<T, E>`` or ``io::Result<T>``
+fn returns_result(function: &Function) -> bool {
+    let Some(returns) = &function.returns else {
+        return false;
+    };
+    let text = returns.iter().map(TypeSegment::text).collect::<String>();
+    let head = text.split('<').next().unwrap_or_default().trim();
+    head.rsplit("::")
+        .next()
+        .is_some_and(|name| name.ends_with("Result"))
+}
+
+/// Check the ``:param name:``, ``:returns:`` and ``:raises:`` fields of function docstrings
+/// against their signatures, adding a diagnostic for each mismatch.
+///
+/// If any parameter is documented, then all named parameters are expected to be,
+/// except for ``self`` and those starting with ``_``.
+///
+/// Only free functions are checked, since the methods of ``impl`` blocks are not analyzed.
+pub fn check_field_lists(result: &mut AnalysisResult) {
+    for function in &result.functions {
+        let mut diagnostic = |code: &str, message: String| {
+            result.diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: code.to_string(),
+                message,
+                file: function.span.file.clone(),
+                item: function.path.clone(),
+                span: Some(function.span.clone()),
+            })
+        };
+        let path = function.path_str();
+        let names = function
+            .parameters
+            .iter()
+            .map(|param| param.name.as_str())
+            .collect::<Vec<_>>();
+        let mut documented = vec![];
+        for field in parse_fields(&function.docstring) {
+            match (field.kind, field.argument) {
+                ("param" | "parameter" | "arg" | "argument" | "key" | "keyword", Some(name)) => {
+                    documented.push(name);
+                    if !names.contains(&name) {
+                        diagnostic(
+                            "unknown-param",
+                            format!("documented parameter `{name}` is not a parameter of `{path}`"),
+                        );
+                    }
+                }
+                ("returns" | "return" | "rtype", _) if function.returns.is_none() => {
+                    diagnostic(
+                        "unexpected-returns",
+                        format!(
+                            "`:{}:` is documented, but `{path}` does not return a value",
+                            field.kind
+                        ),
+                    );
+                }
+                ("raises" | "raise" | "except" | "exception", _) if !returns_result(function) => {
+                    diagnostic(
+                        "unexpected-raises",
+                        format!(
+                            "`:{}:` is documented, but `{path}` does not return a `Result`",
+                            field.kind
+                        ),
+                    );
+                }
+                _ => {}
+            }
+        }
+        if documented.is_empty() {
+            continue;
+        }
+        for name in names {
+            let is_named = name.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if is_named && name != "self" && !name.starts_with('_') && !documented.contains(&name) {
+                diagnostic(
+                    "undocumented-param",
+                    format!("parameter `{name}` of `{path}` is not documented"),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_model::Crate;
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(
+            parse_fields("Summary\n\n:param int x: The x\n:returns: Nothing\nnot :a: field"),
+            vec![
+                DocField {
+                    kind: "param",
+                    argument: Some("x")
+                },
+                DocField {
+                    kind: "returns",
+                    argument: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_field_lists() {
+        let mut result = AnalysisResult::new(Crate {
+            name: "my_crate".into(),
+            version: "0.1.0".into(),
+            ..Default::default()
+        });
+        result.functions.push(Function::parse(
+            None,
+            &["my_crate"],
+            None,
+            &syn::parse_quote! {
+                /// :param a: Documented
+                /// :param b: Documented
+                /// :returns: The result
+                /// :raises Error: If it fails
+                pub fn valid(a: u8, b: u8, _c: u8) -> io::Result<u8> {}
+            },
+        ));
+        result.functions.push(Function::parse(
+            None,
+            &["my_crate"],
+            None,
+            &syn::parse_quote! {
+                /// :param a: Documented
+                /// :param z: Not a parameter
+                /// :returns: Nothing
+                /// :raises Error: Never
+                pub fn invalid(a: u8, b: u8) {}
+            },
+        ));
+        result.functions.push(Function::parse(
+            None,
+            &["my_crate"],
+            None,
+            &syn::parse_quote! {
+                /// No field list
+                pub fn undocumented(a: u8) -> Option<u8> {}
+            },
+        ));
+        check_field_lists(&mut result);
+        let messages = result
+            .diagnostics
+            .iter()
+            .map(|d| format!("{}: {}", d.code, d.message))
+            .collect::<Vec<_>>();
+        assert_yaml_snapshot!(messages, @r###"
+        ---
+        - "unknown-param: documented parameter `z` is not a parameter of `my_crate::invalid`"
+        - "unexpected-returns: `:returns:` is documented, but `my_crate::invalid` does not return a value"
+        - "unexpected-raises: `:raises:` is documented, but `my_crate::invalid` does not return a `Result`"
+        - "undocumented-param: parameter `b` of `my_crate::invalid` is not documented"
+        "###);
+    }
+}
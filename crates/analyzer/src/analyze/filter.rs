@@ -0,0 +1,116 @@
+//! Filter the modules and items of a crate by their paths
+use anyhow::Result;
+use glob::Pattern;
+
+/// Include and exclude glob patterns, matched against the ``::``-joined path of items,
+/// e.g. ``my_crate::api::*`` or ``*::bindings``
+///
+/// An item is excluded if it, or any of its parent modules, matches an ``exclude`` pattern.
+/// Otherwise, if there are ``include`` patterns,
+/// it is included only if it, or any of its parent modules, matches one of them.
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl PathFilter {
+    /// Compile the patterns of a filter
+    ///
+    /// :param include: Patterns of the paths to include; if empty, all paths are included
+    /// :param exclude: Patterns of the paths to exclude
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Pattern::new(pattern).map_err(|e| {
+                        anyhow::anyhow!(format!("Invalid item pattern `{pattern}`: {e}"))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Whether an item is included
+    ///
+    /// :param path: The fully qualified path of the item
+    pub fn includes(&self, path: &[String]) -> bool {
+        let mut ancestors = (1..=path.len()).map(|end| path[..end].join("::"));
+        if self.include.is_empty() {
+            return !ancestors.any(|p| self.exclude.iter().any(|e| e.matches(&p)));
+        }
+        let mut included = false;
+        for ancestor in ancestors {
+            if self.exclude.iter().any(|e| e.matches(&ancestor)) {
+                return false;
+            }
+            included = included || self.include.iter().any(|i| i.matches(&ancestor));
+        }
+        included
+    }
+
+    /// Whether a module should be read, i.e. it is included,
+    /// or it is not excluded and may contain included items
+    ///
+    /// :param path: The fully qualified path of the module
+    pub fn reads(&self, path: &[String]) -> bool {
+        if self.includes(path) {
+            return true;
+        }
+        let excluded = (1..=path.len())
+            .map(|end| path[..end].join("::"))
+            .any(|p| self.exclude.iter().any(|e| e.matches(&p)));
+        if excluded {
+            return false;
+        }
+        // compare the module with the literal start of the patterns, before any wildcard
+        let prefix = format!("{}::", path.join("::"));
+        self.include.iter().any(|pattern| {
+            let literal = pattern
+                .as_str()
+                .split(['*', '?', '['])
+                .next()
+                .unwrap_or_default();
+            literal.starts_with(&prefix) || prefix.starts_with(literal)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Vec<String> {
+        path.split("::").map(String::from).collect()
+    }
+
+    #[test]
+    fn test_path_filter() -> Result<()> {
+        let filter = PathFilter::new(
+            &["my_crate::api::*".into(), "my_crate::Root".into()],
+            &["*::bindings".into(), "my_crate::api::Internal".into()],
+        )?;
+        assert!(filter.includes(&path("my_crate::api::Struct")));
+        assert!(filter.includes(&path("my_crate::api::nested::Struct")));
+        assert!(filter.includes(&path("my_crate::Root")));
+        assert!(!filter.includes(&path("my_crate::Other")));
+        assert!(!filter.includes(&path("my_crate::api::Internal")));
+        assert!(!filter.includes(&path("my_crate::api::bindings::Struct")));
+
+        assert!(filter.reads(&path("my_crate")));
+        assert!(filter.reads(&path("my_crate::api")));
+        assert!(!filter.reads(&path("my_crate::other")));
+        assert!(!filter.reads(&path("my_crate::api::bindings")));
+
+        let filter = PathFilter::new(&[], &["*::bindings".into()])?;
+        assert!(filter.includes(&path("my_crate::Struct")));
+        assert!(!filter.reads(&path("my_crate::bindings")));
+        assert!(PathFilter::new(&["[".into()], &[]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+//! Access to the files of a crate, from disk or from memory
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// The file access required by the analysis,
+/// which must be shareable between threads, since modules are read in parallel
+pub trait FileSystem: Sync {
+    /// Read the contents of a file
+    fn read_to_string(&self, path: &Path) -> Result<String>;
+    /// Whether the path is an existing file
+    fn is_file(&self, path: &Path) -> bool;
+    /// Whether the path is an existing directory
+    fn is_dir(&self, path: &Path) -> bool;
+    /// The paths of the entries in a directory, sorted
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>>;
+    /// Resolve a path to its canonical form, which must exist
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
+    /// Whether the path is an existing file or directory
+    fn exists(&self, path: &Path) -> bool {
+        self.is_file(path) || self.is_dir(path)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// The file system of the machine
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        std::fs::read_to_string(path).context(format!("Error reading: {}", path.to_string_lossy()))
+    }
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let mut entries = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        Ok(entries)
+    }
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        // TODO we use dunce to canonicalize the path because otherwise there is issues with python's os.path.relpath on windows, but maybe we should fix this on the Python side
+        Ok(dunce::canonicalize(path)?)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// A file system held in memory, e.g. for editor buffers, or for testing
+///
+/// Paths are relative to the root of the file system, which is the empty path.
+pub struct MemoryFileSystem {
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl MemoryFileSystem {
+    /// Create a file system from a mapping of paths, e.g. ``src/lib.rs``, to contents
+    pub fn new<P: AsRef<Path>, C: Into<String>>(files: impl IntoIterator<Item = (P, C)>) -> Self {
+        Self {
+            files: files
+                .into_iter()
+                .map(|(path, content)| (normalize(path.as_ref()), content.into()))
+                .collect(),
+        }
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.files
+            .get(&normalize(path))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(format!("File not found: {}", path.to_string_lossy())))
+    }
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.contains_key(&normalize(path))
+    }
+    fn is_dir(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        self.files
+            .keys()
+            .any(|file| file != &path && file.starts_with(&path))
+    }
+    fn read_dir(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        if !self.is_dir(path) {
+            return Err(anyhow::anyhow!(format!(
+                "Directory not found: {}",
+                path.to_string_lossy()
+            )));
+        }
+        let dir = normalize(path);
+        let mut entries = self
+            .files
+            .keys()
+            .filter_map(|file| {
+                let name = file.strip_prefix(&dir).ok()?.components().next()?;
+                Some(dir.join(name))
+            })
+            .collect::<Vec<_>>();
+        entries.dedup();
+        Ok(entries)
+    }
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
+        if !self.exists(path) {
+            return Err(anyhow::anyhow!(format!(
+                "Path not found: {}",
+                path.to_string_lossy()
+            )));
+        }
+        Ok(normalize(path))
+    }
+}
+
+/// Normalize a path lexically, relative to the root of a memory file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    normalized
+}
+
+/// Find the directories matching a glob pattern, relative to a root directory,
+/// e.g. ``crates/*``
+pub(super) fn glob_dirs(fs: &dyn FileSystem, root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![root.to_path_buf()];
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        let component_pattern =
+            glob::Pattern::new(component).context(format!("Invalid glob pattern: {}", pattern))?;
+        let mut matched = vec![];
+        for dir in dirs {
+            if glob::Pattern::escape(component) == component {
+                // a literal component, e.g. ``crates`` or ``..``
+                matched.push(dir.join(component));
+            } else if let Ok(entries) = fs.read_dir(&dir) {
+                matched.extend(entries.into_iter().filter(|entry| {
+                    entry
+                        .file_name()
+                        .is_some_and(|name| component_pattern.matches(&name.to_string_lossy()))
+                }));
+            }
+        }
+        dirs = matched;
+    }
+    dirs.retain(|dir| fs.is_dir(dir));
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_file_system() -> Result<()> {
+        let fs = MemoryFileSystem::new([
+            ("Cargo.toml", ""),
+            ("src/lib.rs", "pub mod a;"),
+            ("./src/a/mod.rs", ""),
+            ("crates/one/Cargo.toml", ""),
+            ("crates/two/Cargo.toml", ""),
+        ]);
+        assert!(fs.is_file(Path::new("src/lib.rs")));
+        assert!(fs.is_file(Path::new("src/a/../a/mod.rs")));
+        assert!(fs.is_dir(Path::new("src")));
+        assert!(fs.is_dir(Path::new("")));
+        assert!(!fs.is_dir(Path::new("src/lib.rs")));
+        assert_eq!(fs.read_to_string(Path::new("src/lib.rs"))?, "pub mod a;");
+        assert_eq!(
+            fs.read_dir(Path::new("src"))?,
+            vec![PathBuf::from("src/a"), PathBuf::from("src/lib.rs")]
+        );
+        assert!(fs.canonicalize(Path::new("other")).is_err());
+        assert_eq!(
+            glob_dirs(&fs, Path::new(""), "crates/*")?,
+            vec![PathBuf::from("crates/one"), PathBuf::from("crates/two")]
+        );
+        Ok(())
+    }
+}
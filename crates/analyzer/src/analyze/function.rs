@@ -1,23 +1,73 @@
-use crate::data_model::Function;
+use quote::quote;
 
-use super::docstring_from_attrs;
+use crate::data_model::{CodeBlock, DocFormat, DocSections, Function, Parameter};
+
+use super::{docstring_from_attrs, feature::cfg_features, span_from_ast, type_::convert_type};
 
 impl Function {
     /// Fully qualified name of the variant
     pub fn path_str(&self) -> String {
         self.path.join("::")
     }
-    pub fn parse(parent: &[&str], ast: &syn::ItemFn) -> Self {
+    /// Extract the relevant information from the AST
+    ///
+    /// :param doc_format: The docstring format inherited from the parent module
+    pub fn parse(
+        file: Option<&str>,
+        parent: &[&str],
+        doc_format: Option<DocFormat>,
+        ast: &syn::ItemFn,
+    ) -> Self {
         let name = ast.sig.ident.to_string();
         let path: Vec<&str> = parent.iter().copied().chain(Some(name.as_str())).collect();
-        let docstring = docstring_from_attrs(&ast.attrs);
+        let (docstring, own_format) = docstring_from_attrs(&ast.attrs);
+        let sections = DocSections::parse(&docstring);
+        let doc_format = own_format.or(doc_format);
+        let code_blocks = CodeBlock::extract(&docstring, doc_format);
         Self {
             path: path.iter().map(|s| s.to_string()).collect(),
+            span: span_from_ast(file, ast),
             docstring,
+            doc_format,
+            links: vec![],
+            sections,
+            code_blocks,
+            source: None,
+            features: cfg_features(&ast.attrs),
+            is_unsafe: ast.sig.unsafety.is_some(),
+            parameters: ast.sig.inputs.iter().map(Parameter::parse).collect(),
+            returns: match &ast.sig.output {
+                syn::ReturnType::Type(_, ty) if !is_unit(ty) => Some(convert_type(ty)),
+                _ => None,
+            },
         }
     }
 }
 
+impl Parameter {
+    /// Extract the relevant information from the AST
+    pub fn parse(ast: &syn::FnArg) -> Self {
+        match ast {
+            syn::FnArg::Receiver(receiver) => Self {
+                name: "self".to_string(),
+                type_: convert_type(&receiver.ty),
+            },
+            syn::FnArg::Typed(typed) => Self {
+                name: match typed.pat.as_ref() {
+                    syn::Pat::Ident(ident) => ident.ident.to_string(),
+                    pat => quote! { #pat }.to_string().replace(" ,", ","),
+                },
+                type_: convert_type(&typed.ty),
+            },
+        }
+    }
+}
+
+/// Whether the type is the unit type ``()``
+fn is_unit(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Tuple(tuple) if tuple.elems.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -30,13 +80,57 @@ mod tests {
             /// This is a docstring
             pub fn my_function() {}
         };
-        let func = Function::parse(&["my_module"], &item);
+        let func = Function::parse(None, &["my_module"], None, &item);
         assert_yaml_snapshot!(func, @r###"
         ---
         path:
           - my_module
           - my_function
+        span:
+          file: ~
+          start_line: 1
+          start_column: 0
+          end_line: 1
+          end_column: 0
         docstring: This is a docstring
+        doc_format: ~
+        links: []
+        sections:
+          summary: This is a docstring
+          body: ""
+          sections: {}
+        code_blocks: []
+        source: ~
+        features: []
+        is_unsafe: false
+        parameters: []
+        returns: ~
+        "###);
+    }
+
+    #[test]
+    fn test_function_parse_signature() {
+        let item: syn::ItemFn = syn::parse_quote! {
+            pub fn my_function(a: u8, (b, c): (u8, u8), _: &str) -> Result<(), String> {}
+        };
+        let func = Function::parse(None, &["my_module"], None, &item);
+        assert_yaml_snapshot!((func.parameters, func.returns), @r###"
+        ---
+        - - name: a
+            type_:
+              - Path: u8
+          - name: "(b, c)"
+            type_:
+              - String: (
+              - Path: u8
+              - String: ", "
+              - Path: u8
+              - String: )
+          - name: _
+            type_:
+              - String: "& "
+              - Path: str
+        - - Path: "Result<() , String>"
         "###);
     }
 }
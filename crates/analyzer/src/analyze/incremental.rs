@@ -0,0 +1,510 @@
+//! Incremental analysis, reusing the modules parsed by a previous analysis
+//! for the files that have not changed
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::data_model::{
+    Changes, Diagnostic, DocFormat, Enum, Function, ItemKind, ItemRef, Module, Span, Struct,
+};
+
+use super::{crate_::AnalysisResult, fs::FileSystem, module::ModuleItems, AnalysisOptions};
+
+#[derive(Debug, Default)]
+/// The modules parsed by previous analyses, with a fingerprint of the files they were parsed from
+///
+/// It is loaded from, and saved to, a manifest file, e.g. next to the cache of the analysis results.
+/// A module file is only parsed again if its content has changed since the previous analysis
+/// of its crate, or if it is now parsed differently, e.g. because its parent module now declares it
+/// under another name, or the analysis options have changed.
+///
+/// Analyzing a crate with the cache also records the hash of each of its items,
+/// to report which items were added, changed or removed since its previous analysis.
+pub struct ModuleCache {
+    /// The manifest that was loaded
+    previous: Manifest,
+    /// The crates analyzed since the manifest was loaded
+    current: Mutex<BTreeMap<String, CrateManifest>>,
+    /// The crate whose modules are being read
+    reading: Mutex<ReadingCrate>,
+}
+
+#[derive(Debug, Default)]
+/// The modules read for the crate being analyzed
+struct ReadingCrate {
+    /// The name of the crate
+    name: String,
+    /// The modules read, by the path to their file
+    modules: BTreeMap<String, CachedModule>,
+    /// The module files parsed, relative to the crate root
+    parsed: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+/// The content of the manifest file
+struct Manifest {
+    /// The version of the analyzer that wrote the manifest,
+    /// since the parsed modules of other versions cannot be reused
+    version: String,
+    /// The analyzed crates, by name
+    crates: BTreeMap<String, CrateManifest>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+/// The fingerprints of an analyzed crate
+struct CrateManifest {
+    /// The parsed modules, by the path to their file
+    modules: BTreeMap<String, CachedModule>,
+    /// A hash of each item of the analysis result, by its kind and fully qualified name
+    items: BTreeMap<String, String>,
+    /// The module files that were parsed in the current analysis, relative to the crate root
+    #[serde(skip)]
+    parsed_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A parsed module file, and the fingerprint of its inputs
+struct CachedModule {
+    /// A hash of the content of the file
+    hash: String,
+    /// A hash of the other inputs of the parse:
+    /// the name of the module, its inherited docstring format and the analysis options
+    key: String,
+    module: ParsedModule,
+    structs: Vec<Struct>,
+    enums: Vec<Enum>,
+    functions: Vec<Function>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A module, with the fields that are only needed during the analysis,
+/// and so are not serialized with it
+struct ParsedModule {
+    module: Module,
+    declaration_features: BTreeMap<String, Vec<String>>,
+    declaration_spans: BTreeMap<String, Span>,
+    inline_modules: Vec<ParsedModule>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl From<Module> for ParsedModule {
+    fn from(mut module: Module) -> Self {
+        Self {
+            declaration_features: std::mem::take(&mut module.declaration_features),
+            declaration_spans: std::mem::take(&mut module.declaration_spans),
+            inline_modules: std::mem::take(&mut module.inline_modules)
+                .into_iter()
+                .map(Self::from)
+                .collect(),
+            diagnostics: std::mem::take(&mut module.diagnostics),
+            module,
+        }
+    }
+}
+
+impl From<ParsedModule> for Module {
+    fn from(parsed: ParsedModule) -> Self {
+        Self {
+            declaration_features: parsed.declaration_features,
+            declaration_spans: parsed.declaration_spans,
+            inline_modules: parsed.inline_modules.into_iter().map(Self::from).collect(),
+            diagnostics: parsed.diagnostics,
+            ..parsed.module
+        }
+    }
+}
+
+impl CachedModule {
+    fn new(hash: String, key: String, items: ModuleItems) -> Self {
+        let (module, structs, enums, functions) = items;
+        Self {
+            hash,
+            key,
+            module: module.into(),
+            structs,
+            enums,
+            functions,
+        }
+    }
+
+    /// The module and its items, as returned by parsing the file
+    fn items(&self) -> ModuleItems {
+        (
+            self.module.clone().into(),
+            self.structs.clone(),
+            self.enums.clone(),
+            self.functions.clone(),
+        )
+    }
+}
+
+impl ModuleCache {
+    /// Load the cache from a manifest file
+    ///
+    /// The cache is empty if the file does not exist, cannot be read,
+    /// or was written by another version of the analyzer, so that everything is parsed again.
+    ///
+    /// :param path: The path to the manifest file
+    pub fn load(path: &Path) -> Self {
+        let previous = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<Manifest>(&content).ok())
+            .filter(|manifest| manifest.version == env!("CARGO_PKG_VERSION"))
+            .unwrap_or_default();
+        Self {
+            previous,
+            ..Default::default()
+        }
+    }
+
+    /// Save the cache to a manifest file,
+    /// keeping the crates that were loaded but not analyzed again
+    ///
+    /// :param path: The path to the manifest file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut crates = self.previous.crates.clone();
+        crates.extend(lock(&self.current).clone());
+        let manifest = Manifest {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            crates,
+        };
+        let content = serde_json::to_string(&manifest)?;
+        std::fs::write(path, content).context(format!(
+            "Error writing manifest: {}",
+            path.to_string_lossy()
+        ))
+    }
+
+    /// Start reading the modules of a crate,
+    /// discarding those read for a previous crate whose analysis did not complete
+    pub(super) fn start_crate(&self, crate_: &str) {
+        *lock(&self.reading) = ReadingCrate {
+            name: crate_.to_string(),
+            ..Default::default()
+        };
+    }
+
+    /// Read a module file, and parse it unless it is unchanged since the previous analysis
+    /// of the crate being read
+    ///
+    /// :param module_path: The path to the module file
+    /// :param mod_path: The fully qualified name of the module
+    /// :param doc_format: The docstring format inherited from the parent module
+    /// :param parse: Parse the content of the file
+    pub(super) fn read_module(
+        &self,
+        fs: &dyn FileSystem,
+        module_path: &Path,
+        mod_path: &[String],
+        doc_format: Option<DocFormat>,
+        options: &AnalysisOptions,
+        parse: impl FnOnce(&str) -> Result<ModuleItems>,
+    ) -> Result<ModuleItems> {
+        let name = module_path.to_string_lossy().to_string();
+        let key = hash(&format!(
+            "{:?}",
+            (
+                mod_path,
+                doc_format,
+                options.function_bodies,
+                options.visibility,
+                options.include_hidden,
+                &options.features,
+            )
+        ));
+        let crate_ = lock(&self.reading).name.clone();
+        let previous = self
+            .previous
+            .crates
+            .get(&crate_)
+            .and_then(|crate_| crate_.modules.get(&name))
+            .filter(|cached| cached.key == key);
+
+        let content = fs.read_to_string(module_path)?;
+        let hash = hash(&content);
+        let (cached, parsed) = match previous {
+            Some(cached) if cached.hash == hash => (cached.clone(), false),
+            _ => (CachedModule::new(hash, key, parse(&content)?), true),
+        };
+        let items = cached.items();
+        let mut reading = lock(&self.reading);
+        if parsed {
+            reading.parsed.extend(items.0.file.clone());
+        }
+        reading.modules.insert(name, cached);
+        Ok(items)
+    }
+
+    /// Record the modules read for the crate being read, once all of its modules have been read
+    pub(super) fn record_modules(&self) {
+        let ReadingCrate {
+            name,
+            modules,
+            mut parsed,
+        } = std::mem::take(&mut *lock(&self.reading));
+        parsed.sort();
+        lock(&self.current).insert(
+            name,
+            CrateManifest {
+                modules,
+                items: BTreeMap::new(),
+                parsed_files: parsed,
+            },
+        );
+    }
+
+    /// Record the items found in the analysis of a crate,
+    /// and compare them to those of its previous analysis
+    pub(super) fn record(&self, result: &AnalysisResult) -> Changes {
+        let mut items = BTreeMap::new();
+        for module in &result.modules {
+            items.insert(item_key(ItemKind::Module, &module.path), hash_item(module));
+        }
+        for struct_ in &result.structs {
+            items.insert(
+                item_key(ItemKind::Struct, &struct_.path),
+                hash_item(struct_),
+            );
+        }
+        for enum_ in &result.enums {
+            items.insert(item_key(ItemKind::Enum, &enum_.path), hash_item(enum_));
+        }
+        for function in &result.functions {
+            items.insert(
+                item_key(ItemKind::Function, &function.path),
+                hash_item(function),
+            );
+        }
+
+        let previous = self
+            .previous
+            .crates
+            .get(&result.crate_.name)
+            .map(|crate_| &crate_.items);
+        let mut current = lock(&self.current);
+        let crate_ = current.entry(result.crate_.name.clone()).or_default();
+        let mut changes = Changes {
+            parsed_files: std::mem::take(&mut crate_.parsed_files),
+            ..Default::default()
+        };
+        for (key, hash) in &items {
+            match previous.and_then(|items| items.get(key)) {
+                None => changes.added.extend(item_ref(key)),
+                Some(previous) if previous != hash => changes.changed.extend(item_ref(key)),
+                Some(_) => {}
+            }
+        }
+        for key in previous.into_iter().flat_map(|items| items.keys()) {
+            if !items.contains_key(key) {
+                changes.removed.extend(item_ref(key));
+            }
+        }
+        crate_.items = items;
+        changes
+    }
+}
+
+/// Start reading the modules of a crate with the cache of the options, if any
+pub(super) fn start_crate(result: &AnalysisResult, options: &AnalysisOptions) {
+    if let Some(cache) = &options.cache {
+        cache.start_crate(&result.crate_.name);
+    }
+}
+
+/// Record the modules read for a crate in the cache of the options, if any
+pub(super) fn record_modules(options: &AnalysisOptions) {
+    if let Some(cache) = &options.cache {
+        cache.record_modules();
+    }
+}
+
+/// Record the analysis of a crate in the cache of the options, if any,
+/// adding the changes since its previous analysis to the result
+pub(super) fn record_changes(result: &mut AnalysisResult, options: &AnalysisOptions) {
+    if let Some(cache) = &options.cache {
+        result.changes = Some(cache.record(result));
+    }
+}
+
+/// Lock a mutex, which cannot be left in an inconsistent state by a panicking thread,
+/// since the data is only ever inserted into or taken
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Hash a string, for comparison with a hash from a previous analysis
+///
+/// The 128-bit FNV-1a hash is used, which, unlike the hasher of the standard library,
+/// is stable across Rust versions and platforms.
+fn hash(content: &str) -> String {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    let hash = content.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u128::from(byte)).wrapping_mul(PRIME)
+    });
+    format!("{:032x}", hash)
+}
+
+/// Hash an item of the analysis result
+fn hash_item(item: &impl Serialize) -> String {
+    hash(&serde_json::to_string(item).unwrap_or_default())
+}
+
+/// The key of an item in the manifest, e.g. ``struct my_crate::Struct``
+fn item_key(kind: ItemKind, path: &[String]) -> String {
+    format!("{} {}", kind.as_str(), path.join("::"))
+}
+
+/// The item referred to by a key of the manifest
+fn item_ref(key: &str) -> Option<ItemRef> {
+    let (kind, path) = key.split_once(' ')?;
+    Some(ItemRef {
+        path: path.split("::").map(String::from).collect(),
+        kind: ItemKind::from_name(kind)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::{analyze_crate, analyze_source};
+    use insta::assert_yaml_snapshot;
+    use std::sync::Arc;
+
+    /// Analyze the crate with the cache in the manifest, then save it
+    fn analyze(path: &Path, manifest: &Path) -> Result<Changes> {
+        let cache = Arc::new(ModuleCache::load(manifest));
+        let options = AnalysisOptions {
+            cache: Some(cache.clone()),
+            ..Default::default()
+        };
+        let result = analyze_crate(&path.to_string_lossy(), &options)?;
+        cache.save(manifest)?;
+        Ok(result.changes.unwrap())
+    }
+
+    #[test]
+    fn test_incremental_analysis() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("my_crate");
+        let manifest = dir.path().join("manifest.json");
+        std::fs::create_dir_all(path.join("src"))?;
+        std::fs::write(
+            path.join("Cargo.toml"),
+            "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"\n",
+        )?;
+        std::fs::write(path.join("src/lib.rs"), "pub mod a;\npub mod b;\n")?;
+        std::fs::write(path.join("src/a.rs"), "pub struct A;\n")?;
+        std::fs::write(path.join("src/b.rs"), "pub struct B;\n")?;
+
+        let changes = analyze(&path, &manifest)?;
+        assert_eq!(
+            changes.parsed_files,
+            vec!["src/a.rs", "src/b.rs", "src/lib.rs"]
+        );
+        assert_eq!(changes.added.len(), 5);
+
+        // nothing is parsed again if the files have not changed,
+        // even if they were written again
+        std::fs::write(path.join("src/b.rs"), "pub struct B;\n")?;
+        assert_eq!(analyze(&path, &manifest)?, Changes::default());
+
+        std::fs::write(path.join("src/lib.rs"), "pub mod a;\npub mod c;\n")?;
+        std::fs::write(path.join("src/a.rs"), "/// A struct\npub struct A;\n")?;
+        std::fs::write(path.join("src/c.rs"), "pub fn c() {}\n")?;
+        assert_yaml_snapshot!(analyze(&path, &manifest)?, @r###"
+        ---
+        added:
+          - path:
+              - my_crate
+              - c
+              - c
+            kind: function
+          - path:
+              - my_crate
+              - c
+            kind: module
+        changed:
+          - path:
+              - my_crate
+            kind: module
+          - path:
+              - my_crate
+              - a
+              - A
+            kind: struct
+        removed:
+          - path:
+              - my_crate
+              - b
+            kind: module
+          - path:
+              - my_crate
+              - b
+              - B
+            kind: struct
+        parsed_files:
+          - src/a.rs
+          - src/c.rs
+          - src/lib.rs
+        "###);
+        Ok(())
+    }
+
+    /// Analyze a crate with a single struct in memory, with the cache
+    fn analyze_struct(name: &str, cache: &Arc<ModuleCache>) -> Result<Changes> {
+        let options = AnalysisOptions {
+            cache: Some(cache.clone()),
+            ..Default::default()
+        };
+        let files = BTreeMap::from([(
+            "src/lib.rs".to_string(),
+            format!("pub struct {};", name.to_uppercase()),
+        )]);
+        Ok(analyze_source(name, &files, &options)?.changes.unwrap())
+    }
+
+    #[test]
+    fn test_incremental_crates_with_same_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let manifest = dir.path().join("manifest.json");
+        let cache = Arc::new(ModuleCache::default());
+        analyze_struct("a", &cache)?;
+        analyze_struct("b", &cache)?;
+        cache.save(&manifest)?;
+
+        // the modules of each crate are reused, although their files have the same path
+        let cache = Arc::new(ModuleCache::load(&manifest));
+        assert_eq!(analyze_struct("a", &cache)?, Changes::default());
+        assert_eq!(analyze_struct("b", &cache)?, Changes::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_after_error() -> Result<()> {
+        let cache = Arc::new(ModuleCache::default());
+        let options = AnalysisOptions {
+            cache: Some(cache.clone()),
+            strict: true,
+            ..Default::default()
+        };
+        let files = BTreeMap::from([
+            (
+                "src/lib.rs".to_string(),
+                "pub mod bad;\npub mod good;".to_string(),
+            ),
+            ("src/bad.rs".to_string(), "pub struct".to_string()),
+            ("src/good.rs".to_string(), "pub struct Good;".to_string()),
+        ]);
+        assert!(analyze_source("broken", &files, &options).is_err());
+
+        // the modules read for the crate that failed are not recorded for the next one
+        let changes = analyze_struct("other", &cache)?;
+        assert_eq!(changes.parsed_files, vec!["src/lib.rs"]);
+        assert!(!lock(&cache.current).contains_key("broken"));
+        Ok(())
+    }
+}
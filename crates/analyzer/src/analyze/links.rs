@@ -0,0 +1,793 @@
+//! Extract and resolve links to other items in docstrings, and paths in types
+//!
+//! This follows the syntax of rustdoc's intra-doc links,
+//! see: https://doc.rust-lang.org/rustdoc/write-documentation/linking-to-items-by-name.html
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use pulldown_cmark::{BrokenLink, CowStr, Event, Options, Parser, Tag, TagEnd};
+
+use crate::data_model::{
+    Diagnostic, DocFormat, DocLink, Import, ItemKind, Severity, Span, TargetKind, TypeSegment,
+};
+
+use super::crate_::AnalysisResult;
+
+impl ItemKind {
+    /// The name of the kind of item
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Module => "module",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Variant => "variant",
+            Self::Field => "field",
+            Self::Function => "function",
+        }
+    }
+    /// Get the kind from its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "module" => Some(Self::Module),
+            "struct" => Some(Self::Struct),
+            "enum" => Some(Self::Enum),
+            "variant" => Some(Self::Variant),
+            "field" => Some(Self::Field),
+            "function" => Some(Self::Function),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a link target looks like a path to an item, e.g. ``crate::Struct``, rather than a URL
+pub fn is_item_path(target: &str) -> bool {
+    parse_target(target).is_some()
+}
+
+/// The target of a link, parsed into a path
+#[derive(Debug, PartialEq)]
+struct LinkTarget {
+    /// The segments of the path
+    path: Vec<String>,
+    /// The kinds of item that the target may resolve to, if restricted by a disambiguator
+    kinds: Option<&'static [ItemKind]>,
+}
+
+/// Parse a link target, e.g. ``struct@crate::module::Struct``,
+/// returning ``None`` if it does not look like a path to an item (e.g. a URL).
+fn parse_target(target: &str) -> Option<LinkTarget> {
+    let target = target.trim().trim_matches('`');
+    // remove any fragment, e.g. `Struct#section`
+    let target = target.split('#').next()?;
+    let (kinds, target) = match target.split_once('@') {
+        Some((prefix, target)) => (Some(disambiguator_kinds(prefix)?), target),
+        None => (None, target),
+    };
+    let (kinds, target) = if let Some(target) = target.strip_suffix("()") {
+        (kinds.or(Some(&[ItemKind::Function][..])), target)
+    } else if let Some(target) = target.strip_suffix('!') {
+        // macros are not analyzed
+        (Some(&[][..]), target)
+    } else {
+        (kinds, target)
+    };
+    let path = target
+        .split("::")
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+    if path
+        .iter()
+        .any(|s| s.is_empty() || !s.chars().all(|c| c.is_alphanumeric() || c == '_'))
+    {
+        return None;
+    }
+    Some(LinkTarget { path, kinds })
+}
+
+/// The kinds of item that a disambiguator prefix, e.g. ``struct@``, refers to
+fn disambiguator_kinds(prefix: &str) -> Option<&'static [ItemKind]> {
+    match prefix {
+        "struct" => Some(&[ItemKind::Struct]),
+        "enum" => Some(&[ItemKind::Enum]),
+        "fn" | "function" | "method" => Some(&[ItemKind::Function]),
+        "mod" | "module" => Some(&[ItemKind::Module]),
+        "variant" => Some(&[ItemKind::Variant]),
+        "field" => Some(&[ItemKind::Field]),
+        "type" => Some(&[ItemKind::Struct, ItemKind::Enum, ItemKind::Module]),
+        "value" => Some(&[ItemKind::Function, ItemKind::Variant]),
+        // these kinds of item are not analyzed
+        "trait" | "union" | "const" | "constant" | "static" | "macro" | "derive" | "attr"
+        | "prim" | "primitive" | "tymethod" => Some(&[]),
+        _ => None,
+    }
+}
+
+/// Extract the links to other items from a Markdown docstring
+///
+/// This includes inline links, ``[text](path)``, reference links, ``[text][ref]`` with ``[ref]: path``,
+/// and shortcut links without a definition, ``[path]``.
+/// Links whose target does not look like a path, such as URLs, are ignored.
+pub fn extract_links(docstring: &str) -> Vec<DocLink> {
+    let mut callback = |link: BrokenLink<'_>| {
+        let reference = link.reference.to_string();
+        parse_target(&reference).map(|_| (CowStr::from(reference), CowStr::Borrowed("")))
+    };
+    let parser =
+        Parser::new_with_broken_link_callback(docstring, Options::empty(), Some(&mut callback));
+    let mut links = vec![];
+    let mut current: Option<DocLink> = None;
+    for event in parser {
+        match (event, &mut current) {
+            (Event::Start(Tag::Link { dest_url, .. }), _) => {
+                current = Some(DocLink {
+                    text: String::new(),
+                    target: dest_url.trim().trim_matches('`').to_string(),
+                    path: None,
+                    kind: None,
+                });
+            }
+            (Event::Text(text) | Event::Code(text), Some(link)) => link.text.push_str(&text),
+            (Event::End(TagEnd::Link), _) => {
+                if let Some(link) = current.take() {
+                    if parse_target(&link.target).is_some() {
+                        links.push(link);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    links
+}
+
+/// The crates of the standard library, which are not analyzed
+const STANDARD_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// The names in scope in every module, through the prelude or as primitive types,
+/// that refer to items of the standard library
+const PRELUDE: &[&str] = &[
+    "Option",
+    "Some",
+    "None",
+    "Result",
+    "Ok",
+    "Err",
+    "Vec",
+    "String",
+    "Box",
+    "ToString",
+    "ToOwned",
+    "Clone",
+    "Copy",
+    "Default",
+    "Drop",
+    "Eq",
+    "PartialEq",
+    "Ord",
+    "PartialOrd",
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "From",
+    "Into",
+    "TryFrom",
+    "TryInto",
+    "AsRef",
+    "AsMut",
+    "Iterator",
+    "IntoIterator",
+    "Extend",
+    "Send",
+    "Sync",
+    "Sized",
+    "Unpin",
+    "bool",
+    "char",
+    "str",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "f32",
+    "f64",
+];
+
+/// The scope in which a docstring's links are resolved
+struct Scope<'a> {
+    /// The fully qualified name of the module containing the item
+    module: &'a [String],
+    /// The fully qualified name of the type that ``Self`` refers to, if any
+    self_type: Option<&'a [String]>,
+}
+
+/// Resolves link targets to analyzed items
+#[derive(Default)]
+struct LinkResolver {
+    /// Fully qualified name -> kind, for all analyzed items
+    items: HashMap<Vec<String>, ItemKind>,
+    /// Fully qualified module name -> the module's imports
+    imports: HashMap<Vec<String>, Vec<Import>>,
+    /// The dependencies of the crate being resolved, that are among the analyzed crates,
+    /// by the name they are referenced with -> crate name
+    externs: BTreeMap<String, String>,
+    /// The crates that the crate being resolved can refer to, but that are not analyzed,
+    /// e.g. ``std`` or a dependency that is not a member of the workspace
+    unanalyzed: BTreeSet<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl LinkResolver {
+    /// Add the items of an analyzed crate
+    fn add(&mut self, result: &AnalysisResult) {
+        for module in &result.modules {
+            self.items.insert(module.path.clone(), ItemKind::Module);
+            self.imports
+                .insert(module.path.clone(), module.imports.clone());
+        }
+        for struct_ in &result.structs {
+            self.items.insert(struct_.path.clone(), ItemKind::Struct);
+            for field in &struct_.fields {
+                self.items.insert(field.path.clone(), ItemKind::Field);
+            }
+        }
+        for enum_ in &result.enums {
+            self.items.insert(enum_.path.clone(), ItemKind::Enum);
+            for variant in &enum_.variants {
+                self.items.insert(variant.path.clone(), ItemKind::Variant);
+                for field in &variant.fields {
+                    self.items.insert(field.path.clone(), ItemKind::Field);
+                }
+            }
+        }
+        for function in &result.functions {
+            self.items.insert(function.path.clone(), ItemKind::Function);
+        }
+    }
+
+    /// Look up a fully qualified path, following the imports of modules (e.g. re-exports)
+    fn lookup(&self, path: &[String], depth: usize) -> Option<(Vec<String>, ItemKind)> {
+        if let Some(kind) = self.items.get(path) {
+            return Some((path.to_vec(), *kind));
+        }
+        // guard against import cycles
+        if depth > 8 {
+            return None;
+        }
+        // find the innermost module in the path, and check its imports for the next segment
+        let i = (1..path.len())
+            .rev()
+            .find(|i| self.imports.contains_key(&path[..*i]))?;
+        let (module, name, rest) = (&path[..i], &path[i], &path[i + 1..]);
+        let imports = &self.imports[module];
+        let named = imports
+            .iter()
+            .filter(|import| &import.name == name)
+            .flat_map(|import| {
+                // imports may be absolute, or relative to the module
+                [
+                    [&self.extern_path(&import.path), rest].concat(),
+                    [module, &import.path[..], rest].concat(),
+                ]
+            });
+        let globbed = imports
+            .iter()
+            .filter(|import| import.name == "*")
+            .map(|import| [&self.extern_path(&import.path), &path[i..]].concat());
+        named
+            .chain(globbed)
+            .find_map(|path| self.lookup(&path, depth + 1))
+    }
+
+    /// Replace the first segment of a path with the crate name, if it is a dependency
+    fn extern_path(&self, path: &[String]) -> Vec<String> {
+        match path.split_first() {
+            Some((first, rest)) => match self.externs.get(first) {
+                Some(crate_name) => [std::slice::from_ref(crate_name), rest].concat(),
+                None => path.to_vec(),
+            },
+            None => vec![],
+        }
+    }
+
+    /// Resolve a link target, relative to the scope of an item
+    fn resolve(&self, target: &LinkTarget, scope: &Scope) -> Option<(Vec<String>, ItemKind)> {
+        let segments = &target.path[..];
+        let candidates = match segments[0].as_str() {
+            "crate" => vec![[&scope.module[..1], &segments[1..]].concat()],
+            "self" => vec![[scope.module, &segments[1..]].concat()],
+            "super" => {
+                let supers = segments.iter().take_while(|s| *s == "super").count();
+                let parent = &scope.module[..scope.module.len().saturating_sub(supers)];
+                vec![[parent, &segments[supers..]].concat()]
+            }
+            "Self" => scope
+                .self_type
+                .map(|self_type| vec![[self_type, &segments[1..]].concat()])
+                .unwrap_or_default(),
+            // in scope of the module, or an absolute path starting with the crate name,
+            // or the name of a dependency
+            first => {
+                let mut candidates = vec![[scope.module, segments].concat()];
+                if scope.module.first().map(String::as_str) == Some(first)
+                    || self.externs.contains_key(first)
+                {
+                    candidates.push(self.extern_path(segments));
+                }
+                candidates
+            }
+        };
+        candidates
+            .iter()
+            .filter_map(|path| self.lookup(path, 0))
+            .find(|(_, kind)| target.kinds.is_none_or(|kinds| kinds.contains(kind)))
+    }
+
+    /// Whether a link target refers to an item that is not analyzed, so cannot be resolved:
+    /// a kind of item that is not analyzed, e.g. ``trait@Trait`` or ``macro!``,
+    /// an item of a crate that is not analyzed, e.g. ``std::vec::Vec``,
+    /// directly or through an import of the module, or an item of the prelude, e.g. ``Option``
+    fn is_unanalyzed(&self, target: &LinkTarget, scope: &Scope) -> bool {
+        if target.kinds.is_some_and(|kinds| kinds.is_empty()) {
+            return true;
+        }
+        let first = &target.path[0];
+        if target.path.len() == 1 && PRELUDE.contains(&first.as_str()) {
+            return true;
+        }
+        let first = self
+            .imports
+            .get(scope.module)
+            .and_then(|imports| imports.iter().find(|import| &import.name == first))
+            .and_then(|import| import.path.first())
+            .unwrap_or(first);
+        self.unanalyzed.contains(first)
+    }
+
+    /// Extract and resolve the links in a Markdown docstring,
+    /// adding a diagnostic for each link that cannot be resolved to an analyzed item,
+    /// unless it refers to an item that is not analyzed, e.g. in ``std``.
+    ///
+    /// :param file: The file containing the item, relative to the crate root
+    /// :param span: The location of the item, if known
+    fn links(
+        &mut self,
+        item: &[String],
+        docstring: &str,
+        doc_format: Option<DocFormat>,
+        scope: Scope,
+        file: Option<&str>,
+        span: Option<&Span>,
+    ) -> Vec<DocLink> {
+        // docstrings without a format are reStructuredText, the default of the Sphinx extension
+        if !matches!(doc_format, Some(DocFormat::Markdown | DocFormat::Rustdoc)) {
+            return vec![];
+        }
+        let mut links = extract_links(docstring);
+        for link in links.iter_mut() {
+            let Some(target) = parse_target(&link.target) else {
+                continue;
+            };
+            if let Some((path, kind)) = self.resolve(&target, &scope) {
+                link.path = Some(path);
+                link.kind = Some(kind);
+            } else if !self.is_unanalyzed(&target, &scope) {
+                self.diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    code: "unresolved-link".to_string(),
+                    message: format!("unresolved link to `{}`", link.target),
+                    file: file.map(String::from),
+                    item: item.to_vec(),
+                    span: span.cloned(),
+                });
+            }
+        }
+        links
+    }
+
+    /// Resolve the paths in a type to analyzed structs and enums
+    fn resolve_types(&self, segments: &mut [TypeSegment], scope: &Scope) {
+        for segment in segments.iter_mut() {
+            let TypeSegment::Path(text) = segment else {
+                continue;
+            };
+            // generic arguments are not resolved
+            let base = text.split('<').next().unwrap_or_default().trim();
+            let Some(mut target) = parse_target(base) else {
+                continue;
+            };
+            target.kinds = Some(&[ItemKind::Struct, ItemKind::Enum]);
+            if let Some((path, kind)) = self.resolve(&target, scope) {
+                *segment = TypeSegment::Resolved {
+                    text: std::mem::take(text),
+                    path,
+                    kind,
+                };
+            }
+        }
+    }
+
+    /// Resolve the links in the docstrings, and the paths in types, of an analyzed crate
+    fn resolve_crate(&mut self, result: &mut AnalysisResult) {
+        let resolver = self;
+        resolver.unanalyzed = STANDARD_CRATES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(
+                result
+                    .crate_
+                    .dependencies
+                    .iter()
+                    .map(|dependency| dependency.name.replace('-', "_")),
+            )
+            .filter(|name| !resolver.externs.contains_key(name))
+            .collect();
+        // the other targets of the crate can refer to its library by name
+        if let Some(lib) = result
+            .crate_
+            .targets
+            .iter()
+            .find(|target| target.kind == TargetKind::Lib)
+        {
+            resolver
+                .externs
+                .entry(lib.module.clone())
+                .or_insert(lib.module.clone());
+        }
+        for module in result.modules.iter_mut() {
+            let scope = Scope {
+                module: &module.path,
+                self_type: None,
+            };
+            module.links = resolver.links(
+                &module.path,
+                &module.docstring,
+                module.doc_format,
+                scope,
+                module.file.as_deref(),
+                None,
+            );
+        }
+        for struct_ in result.structs.iter_mut() {
+            let (module, _) = struct_.path.split_at(struct_.path.len() - 1);
+            let scope = Scope {
+                module,
+                self_type: Some(&struct_.path),
+            };
+            struct_.links = resolver.links(
+                &struct_.path,
+                &struct_.docstring,
+                struct_.doc_format,
+                scope,
+                struct_.span.file.as_deref(),
+                Some(&struct_.span),
+            );
+            for field in struct_.fields.iter_mut() {
+                let scope = Scope {
+                    module,
+                    self_type: Some(&struct_.path),
+                };
+                resolver.resolve_types(&mut field.type_, &scope);
+                field.links = resolver.links(
+                    &field.path,
+                    &field.docstring,
+                    field.doc_format,
+                    scope,
+                    field.span.file.as_deref(),
+                    Some(&field.span),
+                );
+            }
+        }
+        for enum_ in result.enums.iter_mut() {
+            let (module, _) = enum_.path.split_at(enum_.path.len() - 1);
+            let scope = Scope {
+                module,
+                self_type: Some(&enum_.path),
+            };
+            enum_.links = resolver.links(
+                &enum_.path,
+                &enum_.docstring,
+                enum_.doc_format,
+                scope,
+                enum_.span.file.as_deref(),
+                Some(&enum_.span),
+            );
+            for variant in enum_.variants.iter_mut() {
+                let scope = Scope {
+                    module,
+                    self_type: Some(&enum_.path),
+                };
+                variant.links = resolver.links(
+                    &variant.path,
+                    &variant.docstring,
+                    variant.doc_format,
+                    scope,
+                    variant.span.file.as_deref(),
+                    Some(&variant.span),
+                );
+                for field in variant.fields.iter_mut() {
+                    let scope = Scope {
+                        module,
+                        self_type: Some(&enum_.path),
+                    };
+                    resolver.resolve_types(&mut field.type_, &scope);
+                    field.links = resolver.links(
+                        &field.path,
+                        &field.docstring,
+                        field.doc_format,
+                        scope,
+                        field.span.file.as_deref(),
+                        Some(&field.span),
+                    );
+                }
+            }
+        }
+        for function in result.functions.iter_mut() {
+            let (module, _) = function.path.split_at(function.path.len() - 1);
+            let scope = Scope {
+                module,
+                self_type: None,
+            };
+            for param in function.parameters.iter_mut() {
+                resolver.resolve_types(&mut param.type_, &scope);
+            }
+            if let Some(returns) = function.returns.as_mut() {
+                resolver.resolve_types(returns, &scope);
+            }
+            function.links = resolver.links(
+                &function.path,
+                &function.docstring,
+                function.doc_format,
+                scope,
+                function.span.file.as_deref(),
+                Some(&function.span),
+            );
+        }
+        result.diagnostics.append(&mut resolver.diagnostics);
+    }
+}
+
+/// Extract and resolve the links in all docstrings of the analysis result
+///
+/// Links are resolved relative to the module containing the item,
+/// taking into account its imports, with ``Self`` referring to the parent struct or enum.
+/// Only docstrings in Markdown or rustdoc Markdown are searched for links;
+/// those without a format are reStructuredText, which does not use Markdown links.
+/// Links that cannot be resolved are added to the diagnostics of the result,
+/// except those to items that are not analyzed, e.g. of ``std`` or another dependency.
+pub fn resolve_links(result: &mut AnalysisResult) {
+    let mut resolver = LinkResolver::default();
+    resolver.add(result);
+    resolver.resolve_crate(result);
+}
+
+/// Extract and resolve the links in all docstrings, and the paths in types,
+/// of several analyzed crates, e.g. the members of a workspace,
+/// so that they can be resolved to items in each other.
+///
+/// :param externs: For each crate name, its dependencies that are among the crates,
+///     by the name they are referenced with -> crate name
+pub fn resolve_links_across(
+    results: &mut [AnalysisResult],
+    externs: &BTreeMap<String, BTreeMap<String, String>>,
+) {
+    let mut resolver = LinkResolver::default();
+    for result in results.iter() {
+        resolver.add(result);
+    }
+    for result in results.iter_mut() {
+        resolver.externs = externs
+            .get(&result.crate_.name)
+            .cloned()
+            .unwrap_or_default();
+        resolver.resolve_crate(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::AnalysisOptions;
+    use crate::data_model::{Crate, Dependency, DependencyKind, Module};
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            parse_target("`struct@crate::Struct`"),
+            Some(LinkTarget {
+                path: vec!["crate".into(), "Struct".into()],
+                kinds: Some(&[ItemKind::Struct]),
+            })
+        );
+        assert_eq!(
+            parse_target("function()"),
+            Some(LinkTarget {
+                path: vec!["function".into()],
+                kinds: Some(&[ItemKind::Function]),
+            })
+        );
+        assert_eq!(parse_target("https://example.com"), None);
+        assert_eq!(parse_target("user@example.com"), None);
+        assert_eq!(parse_target("some text"), None);
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let docstring = r#"
+See [`Module::parse`], [text](crate::analyze::crate_), [Foo] and [bar][ref].
+Also [a website](https://example.com) and an [undefined reference].
+
+[Foo]: struct@Foo
+[ref]: crate::bar
+"#;
+        assert_yaml_snapshot!(extract_links(docstring), @r###"
+        ---
+        - text: "Module::parse"
+          target: "Module::parse"
+          path: ~
+          kind: ~
+        - text: text
+          target: "crate::analyze::crate_"
+          path: ~
+          kind: ~
+        - text: Foo
+          target: struct@Foo
+          path: ~
+          kind: ~
+        - text: bar
+          target: "crate::bar"
+          path: ~
+          kind: ~
+        "###);
+    }
+
+    #[test]
+    fn test_resolve_links() {
+        let mut result = AnalysisResult::new(Crate {
+            name: "my_crate".into(),
+            version: "0.1.0".into(),
+            dependencies: vec![Dependency {
+                name: "serde".into(),
+                package: None,
+                kind: DependencyKind::Normal,
+                version: Some("1.0".into()),
+                path: None,
+                git: None,
+                features: vec![],
+                default_features: true,
+                optional: false,
+                target: None,
+            }],
+            ..Default::default()
+        });
+        let options = AnalysisOptions::default();
+        let (module, structs, enums, functions) = Module::parse(
+            Some("src/lib.rs"),
+            &["my_crate"],
+            r#"
+//! Links to [`Struct`], [`Struct::field`], [module::Enum] and [`function()`],
+//! and to the items that are not analyzed [`Option`], [`u8`], [`std::vec::Vec`],
+//! [`HashMap`], [`serde::Serialize`] and [`trait@Iterator`], but not [`Missing`]
+use std::collections::HashMap;
+use module::Enum as Renamed;
+pub mod module;
+/// Links to [`Self::field`], [`Renamed::Variant`] and [`crate::missing`]
+pub struct Struct {
+    pub field: u8,
+}
+pub fn function() {}
+"#,
+            Some(DocFormat::Markdown),
+            &options,
+        )
+        .unwrap();
+        result.modules.push(module);
+        result.structs.extend(structs);
+        result.enums.extend(enums);
+        result.functions.extend(functions);
+        let (module, structs, enums, functions) = Module::parse(
+            None,
+            &["my_crate", "module"],
+            r#"
+use super::*;
+/// Links to [`super::Struct`], [`struct@Struct`], [`Self::Variant`] and [`enum@function`]
+pub enum Enum {
+    Variant,
+}
+"#,
+            Some(DocFormat::Markdown),
+            &options,
+        )
+        .unwrap();
+        result.modules.push(module);
+        result.structs.extend(structs);
+        result.enums.extend(enums);
+        result.functions.extend(functions);
+
+        resolve_links(&mut result);
+
+        let links = [
+            &result.modules[0].links,
+            &result.structs[0].links,
+            &result.enums[0].links,
+        ]
+        .iter()
+        .flat_map(|links| links.iter())
+        .map(|link| {
+            format!(
+                "{} -> {}",
+                link.target,
+                link.path
+                    .as_ref()
+                    .map(|p| format!("{:?} {}", link.kind.unwrap(), p.join("::")))
+                    .unwrap_or("~".to_string())
+            )
+        })
+        .collect::<Vec<_>>();
+        assert_yaml_snapshot!(links, @r###"
+        ---
+        - "Struct -> Struct my_crate::Struct"
+        - "Struct::field -> Field my_crate::Struct::field"
+        - "module::Enum -> Enum my_crate::module::Enum"
+        - "function() -> Function my_crate::function"
+        - Option -> ~
+        - u8 -> ~
+        - "std::vec::Vec -> ~"
+        - HashMap -> ~
+        - "serde::Serialize -> ~"
+        - trait@Iterator -> ~
+        - Missing -> ~
+        - "Self::field -> Field my_crate::Struct::field"
+        - "Renamed::Variant -> Variant my_crate::module::Enum::Variant"
+        - "crate::missing -> ~"
+        - "super::Struct -> Struct my_crate::Struct"
+        - "struct@Struct -> Struct my_crate::Struct"
+        - "Self::Variant -> Variant my_crate::module::Enum::Variant"
+        - enum@function -> ~
+        "###);
+        assert_yaml_snapshot!(result.diagnostics, @r###"
+        ---
+        - severity: warning
+          code: unresolved-link
+          message: "unresolved link to `Missing`"
+          file: src/lib.rs
+          item:
+            - my_crate
+          span: ~
+        - severity: warning
+          code: unresolved-link
+          message: "unresolved link to `crate::missing`"
+          file: src/lib.rs
+          item:
+            - my_crate
+            - Struct
+          span:
+            file: src/lib.rs
+            start_line: 8
+            start_column: 0
+            end_line: 11
+            end_column: 1
+        - severity: warning
+          code: unresolved-link
+          message: "unresolved link to `enum@function`"
+          file: ~
+          item:
+            - my_crate
+            - module
+            - Enum
+          span:
+            file: ~
+            start_line: 3
+            start_column: 0
+            end_line: 6
+            end_column: 1
+        "###);
+    }
+}
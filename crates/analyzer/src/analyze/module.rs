@@ -1,12 +1,22 @@
 //! Analyze modules
-use std::path::Path;
-
 use anyhow::Result;
 use syn::parse_file;
 
-use crate::data_model::{Enum, Function, Module, Struct};
+use crate::data_model::{
+    CodeBlock, Diagnostic, DocFormat, DocSections, Enum, Field, Function, Import, Module, Severity,
+    Span, Struct, Visibility,
+};
+
+use super::{
+    docstring::{is_hidden, unreadable_doc_attrs},
+    docstring_from_attrs,
+    error::ParseError,
+    feature::cfg_features,
+    span_from_ast, AnalysisOptions,
+};
 
-use super::docstring_from_attrs;
+/// The module and the public items declared in it
+pub type ModuleItems = (Module, Vec<Struct>, Vec<Enum>, Vec<Function>);
 
 impl Module {
     /// Fully qualified name of the variant
@@ -14,62 +24,403 @@ impl Module {
         self.path.join("::")
     }
     /// Extract the relevant information from the AST
+    ///
+    /// Modules declared inline, e.g. ``mod name { ... }``, are parsed in place,
+    /// and returned as the inline modules of the module.
+    ///
+    /// :param file: The path to the module file, relative to the crate root
+    /// :param path: The fully qualified name of the module
+    /// :param content: The content of the module file
+    /// :param doc_format: The docstring format inherited from the parent module
+    /// :param options: The options for the analysis
     pub fn parse(
-        file: Option<&Path>,
+        file: Option<&str>,
         path: &[&str],
         content: &str,
-    ) -> Result<(Self, Vec<Struct>, Vec<Enum>, Vec<Function>)> {
-        let syntax = parse_file(content)?;
+        doc_format: Option<DocFormat>,
+        options: &AnalysisOptions,
+    ) -> Result<ModuleItems> {
+        let syntax = parse_file(content).map_err(|err| ParseError::new(file, content, &err))?;
+        let mut mod_ = Self::from_attrs(file, path, &syntax.attrs, doc_format);
+        let mut items = FileItems {
+            file,
+            content,
+            options,
+            modules: vec![],
+            structs: vec![],
+            enums: vec![],
+            functions: vec![],
+        };
+        items.parse_items(&mut mod_, &syntax.items);
+        mod_.inline_modules = items.modules;
+        Ok((mod_, items.structs, items.enums, items.functions))
+    }
+
+    /// A module without items, from the attributes of its file or inline declaration
+    ///
+    /// :param doc_format: The docstring format inherited from the parent module
+    fn from_attrs(
+        file: Option<&str>,
+        path: &[&str],
+        attrs: &[syn::Attribute],
+        doc_format: Option<DocFormat>,
+    ) -> Self {
+        let (docstring, own_format) = docstring_from_attrs(attrs);
+        let sections = DocSections::parse(&docstring);
+        let doc_format = own_format.or(doc_format);
+        let code_blocks = CodeBlock::extract(&docstring, doc_format);
         let mut mod_ = Self {
-            file: file.map(|f| f.to_string_lossy().to_string()), // TODO better way to serialize the path, also ?
+            file: file.map(|f| f.to_string()),
             path: path.iter().map(|s| s.to_string()).collect(),
-            docstring: docstring_from_attrs(&syntax.attrs),
+            docstring,
+            doc_format,
+            links: vec![],
+            sections,
+            code_blocks,
+            features: cfg_features(attrs),
             declarations: vec![],
+            declaration_features: Default::default(),
+            declaration_spans: Default::default(),
+            inline_modules: vec![],
+            diagnostics: vec![],
+            imports: vec![],
         };
+        check_doc_attrs(&mut mod_.diagnostics, file, &mod_.path, attrs);
+        mod_
+    }
+
+    /// An empty module, in place of one whose file could not be read or parsed
+    ///
+    /// :param file: The path to the module file, relative to the crate root
+    /// :param path: The fully qualified name of the module
+    pub fn stub(file: Option<&str>, path: &[String]) -> Self {
+        Self {
+            file: file.map(|f| f.to_string()),
+            path: path.to_vec(),
+            docstring: String::new(),
+            doc_format: None,
+            links: vec![],
+            sections: DocSections::default(),
+            code_blocks: vec![],
+            features: vec![],
+            declarations: vec![],
+            declaration_features: Default::default(),
+            declaration_spans: Default::default(),
+            inline_modules: vec![],
+            diagnostics: vec![],
+            imports: vec![],
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self).unwrap()
+    }
+}
 
-        let mut structs = vec![];
-        let mut enums = vec![];
-        let mut functions = vec![];
+/// The items found while parsing a module file
+struct FileItems<'a> {
+    /// The path to the module file, relative to the crate root
+    file: Option<&'a str>,
+    /// The content of the module file
+    content: &'a str,
+    options: &'a AnalysisOptions,
+    /// The modules declared inline in the file, in the order of their declarations
+    modules: Vec<Module>,
+    structs: Vec<Struct>,
+    enums: Vec<Enum>,
+    functions: Vec<Function>,
+}
 
-        for item in syntax.items {
+impl FileItems<'_> {
+    /// Whether an item with this visibility and attributes is included in the analysis
+    fn includes(&self, vis: &syn::Visibility, attrs: &[syn::Attribute]) -> bool {
+        self.options.includes(
+            Visibility::from_ast(vis),
+            is_hidden(attrs),
+            &cfg_features(attrs),
+        )
+    }
+
+    /// Parse the items of a module, and recursively of the modules declared inline in it
+    fn parse_items(&mut self, mod_: &mut Module, items: &[syn::Item]) {
+        let file = self.file;
+        let path = mod_.path.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        let path = path.as_slice();
+        let doc_format = mod_.doc_format;
+        for item in items {
             // TODO traits, functions, impls, et
-            match &item {
-                syn::Item::Mod(mod_item) => {
-                    if let syn::Visibility::Public(_) = mod_item.vis {
-                        // TODO handle modules that are not just declarations
-                        mod_.declarations.push(mod_item.ident.to_string());
+            match item {
+                syn::Item::Mod(mod_item) if self.includes(&mod_item.vis, &mod_item.attrs) => {
+                    let name = mod_item.ident.to_string();
+                    if let Some((_, items)) = &mod_item.content {
+                        let inline_path = [path, &[name.as_str()]].concat();
+                        let mut inline =
+                            Module::from_attrs(file, &inline_path, &mod_item.attrs, doc_format);
+                        // the inline module precedes those declared within it
+                        let index = self.modules.len();
+                        self.parse_items(&mut inline, items);
+                        // the problems found in the file are reported with its module
+                        mod_.diagnostics.append(&mut inline.diagnostics);
+                        self.modules.insert(index, inline);
+                        continue;
                     }
-                }
-                syn::Item::Struct(struct_item) => {
-                    if let syn::Visibility::Public(_) = struct_item.vis {
-                        let struct_ = Struct::parse(path, struct_item);
-                        structs.push(struct_);
+                    let features = cfg_features(&mod_item.attrs);
+                    if !features.is_empty() {
+                        mod_.declaration_features.insert(name.clone(), features);
                     }
+                    mod_.declaration_spans
+                        .insert(name.clone(), span_from_ast(file, mod_item));
+                    mod_.declarations.push(name);
                 }
-                syn::Item::Enum(enum_item) => {
-                    if let syn::Visibility::Public(_) = enum_item.vis {
-                        let enum_ = Enum::parse(path, enum_item);
-                        enums.push(enum_);
-                    }
+                syn::Item::Use(use_item) => {
+                    mod_.imports
+                        .extend(Import::from_use_tree(path, &use_item.tree));
+                }
+                syn::Item::Struct(struct_item)
+                    if self.includes(&struct_item.vis, &struct_item.attrs) =>
+                {
+                    let mut struct_ =
+                        Struct::parse(file, path, doc_format, struct_item, self.options);
+                    struct_.source = Some(source_snippet(self.content, &struct_.span));
+                    check_doc_attrs(
+                        &mut mod_.diagnostics,
+                        file,
+                        &struct_.path,
+                        &struct_item.attrs,
+                    );
+                    check_field_doc_attrs(
+                        &mut mod_.diagnostics,
+                        file,
+                        &struct_.fields,
+                        &struct_item.fields,
+                    );
+                    self.structs.push(struct_);
                 }
-                syn::Item::Fn(fn_item) => {
-                    if let syn::Visibility::Public(_) = fn_item.vis {
-                        let function = Function::parse(path, fn_item);
-                        functions.push(function);
+                syn::Item::Enum(enum_item) if self.includes(&enum_item.vis, &enum_item.attrs) => {
+                    let mut enum_ = Enum::parse(file, path, doc_format, enum_item, self.options);
+                    enum_.source = Some(source_snippet(self.content, &enum_.span));
+                    check_doc_attrs(&mut mod_.diagnostics, file, &enum_.path, &enum_item.attrs);
+                    for (variant, variant_item) in enum_.variants.iter().zip(&enum_item.variants) {
+                        check_doc_attrs(
+                            &mut mod_.diagnostics,
+                            file,
+                            &variant.path,
+                            &variant_item.attrs,
+                        );
+                        check_field_doc_attrs(
+                            &mut mod_.diagnostics,
+                            file,
+                            &variant.fields,
+                            &variant_item.fields,
+                        );
                     }
+                    self.enums.push(enum_);
+                }
+                syn::Item::Fn(fn_item) if self.includes(&fn_item.vis, &fn_item.attrs) => {
+                    let mut function = Function::parse(file, path, doc_format, fn_item);
+                    check_doc_attrs(&mut mod_.diagnostics, file, &function.path, &fn_item.attrs);
+                    function.source = Some(if self.options.function_bodies {
+                        source_snippet(self.content, &function.span)
+                    } else {
+                        // cut the source at the start of the body, keeping the signature as written
+                        let body = span_from_ast(file, &fn_item.block);
+                        let span = Span {
+                            end_line: body.start_line,
+                            end_column: body.start_column,
+                            ..function.span.clone()
+                        };
+                        let signature = source_snippet(self.content, &span);
+                        let signature = signature.trim_end();
+                        match &fn_item.sig.generics.where_clause {
+                            // a where clause spanning several lines is followed by the body
+                            // on its own line, as formatted by rustfmt
+                            Some(where_clause) => {
+                                let where_line = where_clause.where_token.span.start().line;
+                                if where_line < body.start_line {
+                                    format!("{signature}\n{{ ... }}")
+                                } else {
+                                    format!("{signature} {{ ... }}")
+                                }
+                            }
+                            None => format!("{signature} {{ ... }}"),
+                        }
+                    });
+                    self.functions.push(function);
                 }
                 _ => {}
             }
         }
+    }
+}
 
-        Ok((mod_, structs, enums, functions))
+/// Add a diagnostic for each ``doc`` attribute of an item that cannot be read
+///
+/// :param item: The fully qualified name of the item
+fn check_doc_attrs(
+    diagnostics: &mut Vec<Diagnostic>,
+    file: Option<&str>,
+    item: &[String],
+    attrs: &[syn::Attribute],
+) {
+    for attr in unreadable_doc_attrs(attrs) {
+        let span = span_from_ast(file, attr);
+        diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            code: "unreadable-doc-attribute".to_string(),
+            message: format!(
+                "`doc` attribute of `{}` cannot be read, so it is omitted from the docstring",
+                item.join("::")
+            ),
+            file: span.file.clone(),
+            item: item.to_vec(),
+            span: Some(span),
+        });
     }
+}
 
-    pub fn to_json(&self) -> String {
-        serde_json::to_string(&self).unwrap()
+impl Import {
+    /// Flatten a ``use`` tree into the names that it brings into scope
+    ///
+    /// :param module: The fully qualified name of the module containing the declaration
+    /// :param tree: The ``use`` tree
+    pub fn from_use_tree(module: &[&str], tree: &syn::UseTree) -> Vec<Self> {
+        let mut imports = vec![];
+        flatten_use_tree(module, vec![], tree, &mut imports);
+        imports
+    }
+}
+
+fn flatten_use_tree(
+    module: &[&str],
+    mut prefix: Vec<String>,
+    tree: &syn::UseTree,
+    imports: &mut Vec<Import>,
+) {
+    match tree {
+        syn::UseTree::Path(use_path) => {
+            push_use_segment(module, &mut prefix, &use_path.ident.to_string());
+            flatten_use_tree(module, prefix, &use_path.tree, imports);
+        }
+        syn::UseTree::Name(use_name) => {
+            let name = use_name.ident.to_string();
+            if name == "self" {
+                // e.g. `use a::b::{self}`
+                if let Some(name) = prefix.last().cloned() {
+                    imports.push(Import { name, path: prefix });
+                }
+            } else {
+                push_use_segment(module, &mut prefix, &name);
+                imports.push(Import { name, path: prefix });
+            }
+        }
+        syn::UseTree::Rename(use_rename) => {
+            let name = use_rename.rename.to_string();
+            if name != "_" {
+                push_use_segment(module, &mut prefix, &use_rename.ident.to_string());
+                imports.push(Import { name, path: prefix });
+            }
+        }
+        syn::UseTree::Glob(_) => imports.push(Import {
+            name: "*".to_string(),
+            path: prefix,
+        }),
+        syn::UseTree::Group(group) => {
+            for tree in &group.items {
+                flatten_use_tree(module, prefix.clone(), tree, imports);
+            }
+        }
     }
 }
 
+/// Add a segment to a ``use`` path, replacing ``crate``, ``self`` and ``super``
+fn push_use_segment(module: &[&str], path: &mut Vec<String>, segment: &str) {
+    match (path.is_empty(), segment) {
+        (true, "crate") => path.extend(module.iter().take(1).map(|s| s.to_string())),
+        (true, "self") => path.extend(module.iter().map(|s| s.to_string())),
+        (true, "super") => path.extend(
+            module
+                .iter()
+                .take(module.len().saturating_sub(1))
+                .map(|s| s.to_string()),
+        ),
+        (false, "super") => {
+            path.pop();
+        }
+        _ => path.push(segment.to_string()),
+    }
+}
+
+/// Add a diagnostic for each ``doc`` attribute of the analyzed fields of a struct or variant
+/// that cannot be read
+///
+/// :param fields: The fields that were analyzed
+/// :param ast: All the fields of the struct or variant
+fn check_field_doc_attrs(
+    diagnostics: &mut Vec<Diagnostic>,
+    file: Option<&str>,
+    fields: &[Field],
+    ast: &syn::Fields,
+) {
+    for (position, field_item) in ast.iter().enumerate() {
+        let name = field_item
+            .ident
+            .as_ref()
+            .map_or(position.to_string(), |name| name.to_string());
+        if let Some(field) = fields.iter().find(|field| field.path.last() == Some(&name)) {
+            check_doc_attrs(diagnostics, file, &field.path, &field_item.attrs);
+        }
+    }
+}
+
+/// Extract the source code of an item, as written in the module content.
+///
+/// Any indentation of the first line is retained,
+/// then the indentation common to all lines is removed.
+///
+/// :param content: The content of the module file
+/// :param span: The location of the item in the module file
+fn source_snippet(content: &str, span: &Span) -> String {
+    // the BOM is stripped before parsing, so spans do not account for it
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let (line_start, _) = byte_offset(content, span.start_line, 0);
+    let (start, indent_only) = byte_offset(content, span.start_line, span.start_column);
+    let (end, _) = byte_offset(content, span.end_line, span.end_column);
+    let text = if indent_only {
+        &content[line_start..end]
+    } else {
+        &content[start..end]
+    };
+    let indent = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    text.lines()
+        .map(|line| line.get(indent..).unwrap_or(line.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Convert a line (1-based) and column (0-based, in characters) to a byte offset.
+///
+/// Also returns whether the text preceding the column on that line is only whitespace.
+fn byte_offset(content: &str, line: usize, column: usize) -> (usize, bool) {
+    let line_start = content
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(|l| l.len())
+        .sum::<usize>();
+    let line_text = &content[line_start..];
+    let offset = line_text
+        .char_indices()
+        .nth(column)
+        .map(|(i, _)| i)
+        .unwrap_or(line_text.len());
+    let indent_only = line_text[..offset].trim().is_empty();
+    (line_start + offset, indent_only)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,28 +436,161 @@ pub enum MyEnum {
     MyVariant1,
 }
 "###;
-        let mod_ = Module::parse(None, &["test"], content).unwrap();
+        let mod_ =
+            Module::parse(None, &["test"], content, None, &AnalysisOptions::default()).unwrap();
         assert_yaml_snapshot!(mod_, @r###"
         ---
         - file: ~
           path:
             - test
           docstring: "Multi-line\ndocstring"
+          doc_format: ~
+          links: []
+          sections:
+            summary: Multi-line docstring
+            body: ""
+            sections: {}
+          code_blocks: []
+          features: []
           declarations: []
+          imports: []
         - []
         - - path:
               - test
               - MyEnum
+            span:
+              file: ~
+              start_line: 5
+              start_column: 0
+              end_line: 7
+              end_column: 1
             docstring: ""
+            doc_format: ~
+            links: []
+            sections:
+              summary: ""
+              body: ""
+              sections: {}
+            code_blocks: []
+            source: "pub enum MyEnum {\n    MyVariant1,\n}"
+            features: []
             variants:
               - path:
                   - test
                   - MyEnum
                   - MyVariant1
+                span:
+                  file: ~
+                  start_line: 6
+                  start_column: 4
+                  end_line: 6
+                  end_column: 14
                 docstring: ""
+                doc_format: ~
+                links: []
+                sections:
+                  summary: ""
+                  body: ""
+                  sections: {}
+                code_blocks: []
                 discriminant: ~
                 fields: []
         - []
         "###);
     }
+
+    #[test]
+    fn test_parse_module_source() {
+        let content = r###"
+/// A function
+pub fn my_function(a: u8)
+where
+    u8: Copy,
+{
+    let _ = a;
+}
+"###;
+        let (_, _, _, functions) =
+            Module::parse(None, &["test"], content, None, &AnalysisOptions::default()).unwrap();
+        assert_eq!(
+            functions[0].source.as_deref(),
+            Some("/// A function\npub fn my_function(a: u8)\nwhere\n    u8: Copy,\n{\n    let _ = a;\n}")
+        );
+
+        let options = AnalysisOptions {
+            function_bodies: false,
+            ..Default::default()
+        };
+        let (_, _, _, functions) = Module::parse(None, &["test"], content, None, &options).unwrap();
+        assert_eq!(
+            functions[0].source.as_deref(),
+            Some("/// A function\npub fn my_function(a: u8)\nwhere\n    u8: Copy,\n{ ... }")
+        );
+
+        let content = r###"
+pub fn inline<T>(t: T) -> T where T: Copy { t }
+
+pub fn multiline<T, U>(t: T, u: U)
+where
+    T: Copy,
+    U: Clone + Default,
+{
+}
+"###;
+        let (_, _, _, functions) = Module::parse(None, &["test"], content, None, &options).unwrap();
+        assert_yaml_snapshot!(functions.iter().map(|f| &f.source).collect::<Vec<_>>(), @r###"
+        ---
+        - "pub fn inline<T>(t: T) -> T where T: Copy { ... }"
+        - "pub fn multiline<T, U>(t: T, u: U)\nwhere\n    T: Copy,\n    U: Clone + Default,\n{ ... }"
+        "###);
+
+        // the snippet is dedented, and comments are retained
+        let content = "pub mod a {}\n    /// A docstring\n    pub struct MyStruct {\n        // a comment\n        pub field: u8,\n    }\n";
+        let (_, structs, _, _) =
+            Module::parse(None, &["test"], content, None, &AnalysisOptions::default()).unwrap();
+        assert_eq!(
+            structs[0].source.as_deref(),
+            Some("/// A docstring\npub struct MyStruct {\n    // a comment\n    pub field: u8,\n}")
+        );
+    }
+
+    #[test]
+    fn test_parse_module_doc_format() {
+        let content = r###"
+//! <!-- doc-format: markdown -->
+//! Module docstring
+
+pub struct MyStruct {
+    /// field docstring
+    pub field: u8,
+}
+
+#[doc(format = "rst")]
+pub enum MyEnum {
+    MyVariant1,
+}
+"###;
+        let (module, structs, enums, _) =
+            Module::parse(None, &["test"], content, None, &AnalysisOptions::default()).unwrap();
+        assert_eq!(module.docstring, "Module docstring");
+        assert_eq!(module.doc_format, Some(DocFormat::Markdown));
+        assert_eq!(structs[0].doc_format, Some(DocFormat::Markdown));
+        assert_eq!(structs[0].fields[0].doc_format, Some(DocFormat::Markdown));
+        assert_eq!(enums[0].doc_format, Some(DocFormat::Restructuredtext));
+        assert_eq!(
+            enums[0].variants[0].doc_format,
+            Some(DocFormat::Restructuredtext)
+        );
+
+        // the format is inherited from the parent module
+        let (module, _, _, _) = Module::parse(
+            None,
+            &["test"],
+            "",
+            Some(DocFormat::Restructuredtext),
+            &AnalysisOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(module.doc_format, Some(DocFormat::Restructuredtext));
+    }
 }
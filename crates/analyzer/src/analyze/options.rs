@@ -0,0 +1,67 @@
+//! Options to control the analysis
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::data_model::{DocFormat, Visibility};
+
+use super::incremental::ModuleCache;
+
+#[derive(Debug, Clone)]
+/// Options to control the analysis
+pub struct AnalysisOptions {
+    /// Whether to include the bodies of functions in their source code snippets
+    pub function_bodies: bool,
+    /// The minimum visibility of items to include
+    pub visibility: Visibility,
+    /// Whether to include items marked with ``#[doc(hidden)]``
+    pub include_hidden: bool,
+    /// The enabled features; items gated on other features are not included.
+    /// If not set, all features are considered enabled.
+    pub features: Option<Vec<String>>,
+    /// Glob patterns of item paths to include; if empty, all items are included
+    pub include: Vec<String>,
+    /// Glob patterns of item paths to exclude, which take precedence over ``include``
+    pub exclude: Vec<String>,
+    /// Whether to stop the analysis at the first module that cannot be read or parsed,
+    /// rather than adding an error diagnostic and continuing, e.g. for CI
+    pub strict: bool,
+    /// The default format of docstrings, by crate name, for crates that do not configure one;
+    /// docstrings without a format are otherwise considered to be reStructuredText
+    pub doc_formats: BTreeMap<String, DocFormat>,
+    /// The modules parsed by previous analyses,
+    /// to reuse for the files that have not changed since
+    pub cache: Option<Arc<ModuleCache>>,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        Self {
+            function_bodies: true,
+            visibility: Visibility::Public,
+            include_hidden: false,
+            features: None,
+            include: vec![],
+            exclude: vec![],
+            strict: false,
+            doc_formats: BTreeMap::new(),
+            cache: None,
+        }
+    }
+}
+
+impl AnalysisOptions {
+    /// Whether an item with the given visibility, attributes and feature gates should be included
+    pub(super) fn includes(
+        &self,
+        visibility: Visibility,
+        hidden: bool,
+        features: &[String],
+    ) -> bool {
+        visibility <= self.visibility
+            && (self.include_hidden || !hidden)
+            && self
+                .features
+                .as_ref()
+                .is_none_or(|enabled| features.iter().all(|f| enabled.contains(f)))
+    }
+}
@@ -0,0 +1,191 @@
+//! Analyze packaged crates, e.g. third-party dependencies
+use std::io::Read;
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result};
+
+use crate::data_model::{CrateSource, SourceKind};
+
+use super::{
+    crate_::{analyze_crate_in, AnalysisResult},
+    fs::{MemoryFileSystem, RealFileSystem},
+    AnalysisOptions,
+};
+
+impl SourceKind {
+    /// The name of the kind of source, e.g. ``vendor``
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceKind::Archive => "archive",
+            SourceKind::Vendor => "vendor",
+            SourceKind::Registry => "registry",
+        }
+    }
+}
+
+/// Analyze a packaged crate, which is read without building or fetching anything
+///
+/// The package can be:
+///
+/// - a ``.crate`` archive, as created by ``cargo package`` or downloaded from a registry,
+///   which is read in memory, without extracting it to disk
+/// - a crate directory in the output of ``cargo vendor``, i.e. containing a ``.cargo-checksum.json``
+/// - an extracted crate in the cargo registry, e.g. ``~/.cargo/registry/src/<registry>/<name>-<version>``
+///
+/// :param path: The path to the archive or crate directory
+pub fn analyze_package(path: &str, options: &AnalysisOptions) -> Result<AnalysisResult> {
+    let package = Path::new(path);
+    let (mut result, kind, registry) = if package.is_file() {
+        let (fs, root) = read_archive(package)?;
+        let result = analyze_crate_in(&fs, &root, options)?;
+        (result, SourceKind::Archive, None)
+    } else if package.is_dir() {
+        let package = dunce::canonicalize(package)?;
+        let (kind, registry) = if package.join(".cargo-checksum.json").is_file() {
+            (SourceKind::Vendor, None)
+        } else if let Some(registry) = registry_name(&package) {
+            (SourceKind::Registry, Some(registry))
+        } else {
+            return Err(anyhow::anyhow!(format!(
+                "Not a packaged crate (use analyze_crate for a local crate): {}",
+                path
+            )));
+        };
+        let result = analyze_crate_in(&RealFileSystem, &package.to_string_lossy(), options)?;
+        (result, kind, registry)
+    } else {
+        return Err(anyhow::anyhow!(format!("Package does not exist: {}", path)));
+    };
+    result.crate_.source = Some(CrateSource {
+        kind,
+        path: path.to_string(),
+        registry,
+    });
+    Ok(result)
+}
+
+/// Read the files of a ``.crate`` archive, which is a gzipped tarball
+/// with a single ``<name>-<version>`` directory
+///
+/// Files that are not valid UTF-8, e.g. images, are skipped.
+///
+/// :return: The files of the archive, and the path to the crate directory within it
+fn read_archive(path: &Path) -> Result<(MemoryFileSystem, String)> {
+    let context = || format!("Error reading archive: {}", path.to_string_lossy());
+    let file = std::fs::File::open(path).with_context(context)?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut files = vec![];
+    let mut root = None;
+    for entry in archive.entries().with_context(context)? {
+        let mut entry = entry.with_context(context)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let entry_path = entry.path().with_context(context)?.into_owned();
+        if let Some(Component::Normal(dir)) = entry_path.components().next() {
+            root.get_or_insert_with(|| dir.to_string_lossy().to_string());
+        }
+        let mut contents = vec![];
+        entry.read_to_end(&mut contents).with_context(context)?;
+        if let Ok(contents) = String::from_utf8(contents) {
+            files.push((entry_path, contents));
+        }
+    }
+    let Some(root) = root else {
+        return Err(anyhow::anyhow!(format!(
+            "Archive is empty: {}",
+            path.to_string_lossy()
+        )));
+    };
+    Ok((MemoryFileSystem::new(files), root))
+}
+
+/// Get the name of the registry of a crate directory in ``registry/src/<registry>/``
+fn registry_name(path: &Path) -> Option<String> {
+    let registry = path.parent()?;
+    let src = registry.parent()?;
+    if src.file_name()? == "src" && src.parent()?.file_name()? == "registry" {
+        Some(registry.file_name()?.to_string_lossy().to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARGO_TOML: &str = r#"
+[package]
+name = "packaged"
+version = "0.1.0"
+"#;
+    const LIB_RS: &str = "//! A packaged crate\n\n/// A struct\npub struct Packaged;\n";
+
+    #[test]
+    fn test_analyze_archive() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let archive = dir.path().join("packaged-0.1.0.crate");
+        let encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&archive)?,
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for (file, contents) in [
+            ("packaged-0.1.0/Cargo.toml", CARGO_TOML),
+            ("packaged-0.1.0/src/lib.rs", LIB_RS),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, file, contents.as_bytes())?;
+        }
+        builder.into_inner()?.finish()?;
+
+        let path = archive.to_string_lossy().to_string();
+        let result = analyze_package(&path, &AnalysisOptions::default())?;
+        assert_eq!(result.crate_.name, "packaged");
+        assert_eq!(result.structs[0].path, vec!["packaged", "Packaged"]);
+        assert_eq!(
+            result.crate_.source,
+            Some(CrateSource {
+                kind: SourceKind::Archive,
+                path,
+                registry: None,
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_vendored() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let vendor = dir.path().join("vendor/packaged");
+        std::fs::create_dir_all(vendor.join("src"))?;
+        std::fs::write(vendor.join("Cargo.toml"), CARGO_TOML)?;
+        std::fs::write(vendor.join("src/lib.rs"), LIB_RS)?;
+        let path = vendor.to_string_lossy().to_string();
+        assert!(analyze_package(&path, &AnalysisOptions::default()).is_err());
+
+        std::fs::write(vendor.join(".cargo-checksum.json"), "{}")?;
+        let result = analyze_package(&path, &AnalysisOptions::default())?;
+        assert_eq!(
+            result.crate_.source.map(|s| s.kind),
+            Some(SourceKind::Vendor)
+        );
+        assert_eq!(result.structs.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_name() {
+        assert_eq!(
+            registry_name(Path::new(
+                "/home/user/.cargo/registry/src/index.crates.io-6f17d22bba15001f/serde-1.0.0"
+            )),
+            Some("index.crates.io-6f17d22bba15001f".to_string())
+        );
+        assert_eq!(registry_name(Path::new("/home/user/vendor/serde")), None);
+    }
+}
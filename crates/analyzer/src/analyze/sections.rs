@@ -0,0 +1,208 @@
+//! Split docstrings into sections
+use crate::data_model::{Diagnostic, DocSections, Severity};
+
+use super::crate_::AnalysisResult;
+
+impl DocSections {
+    /// Split a docstring into its summary, body and sections
+    ///
+    /// Sections start at a top-level ``# Heading`` line,
+    /// ignoring lines in fenced code blocks, where ``# `` marks a hidden doctest line.
+    /// The content of repeated headings is concatenated.
+    pub fn parse(docstring: &str) -> Self {
+        let mut preamble = vec![];
+        let mut sections: Vec<(String, Vec<&str>)> = vec![];
+        // the character and length of the fence of the current code block
+        let mut fence: Option<(char, usize)> = None;
+        for line in docstring.lines() {
+            let trimmed = line.trim_start();
+            if let Some((char, len)) = fence {
+                if trimmed.chars().take_while(|c| *c == char).count() >= len
+                    && trimmed.trim_start_matches(char).trim().is_empty()
+                {
+                    fence = None;
+                }
+            } else if let Some(opened) = code_fence(trimmed) {
+                fence = Some(opened);
+            } else if let Some(title) = heading(line) {
+                sections.push((title.to_string(), vec![]));
+                continue;
+            }
+            match sections.last_mut() {
+                Some((_, lines)) => lines.push(line),
+                None => preamble.push(line),
+            }
+        }
+
+        let preamble = trim_blank_lines(&preamble);
+        let summary_end = preamble
+            .iter()
+            .position(|line| line.trim().is_empty())
+            .unwrap_or(preamble.len());
+        let mut result = Self {
+            summary: preamble[..summary_end]
+                .iter()
+                .map(|line| line.trim())
+                .collect::<Vec<_>>()
+                .join(" "),
+            body: trim_blank_lines(&preamble[summary_end..]).join("\n"),
+            ..Default::default()
+        };
+        for (title, lines) in sections {
+            let content = trim_blank_lines(&lines).join("\n");
+            result
+                .sections
+                .entry(title)
+                .and_modify(|existing| {
+                    if !content.is_empty() {
+                        existing.push_str("\n\n");
+                        existing.push_str(&content);
+                    }
+                })
+                .or_insert(content);
+        }
+        result
+    }
+}
+
+/// If the line opens a fenced code block, return the character and length of the fence
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let char = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|c| *c == char).count();
+    (len >= 3).then_some((char, len))
+}
+
+/// If the line is a top-level Markdown heading, return its title
+fn heading(line: &str) -> Option<&str> {
+    let title = line.strip_prefix("# ")?.trim().trim_end_matches('#').trim();
+    (!title.is_empty()).then_some(title)
+}
+
+/// Remove blank lines from the start and end
+fn trim_blank_lines<'a>(lines: &'a [&'a str]) -> &'a [&'a str] {
+    let start = lines
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(start, |i| i + 1);
+    &lines[start..end]
+}
+
+/// Add a diagnostic for each ``unsafe`` function without a ``# Safety`` section in its docstring
+pub fn check_safety_sections(result: &mut AnalysisResult) {
+    for function in &result.functions {
+        if function.is_unsafe && !function.sections.sections.contains_key("Safety") {
+            result.diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "missing-safety-section".to_string(),
+                message: format!(
+                    "unsafe function `{}` has no `# Safety` section",
+                    function.path_str()
+                ),
+                file: function.span.file.clone(),
+                item: function.path.clone(),
+                span: Some(function.span.clone()),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_model::{Crate, Function};
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_parse_sections() {
+        let docstring = r#"The summary,
+over two lines.
+
+The body.
+
+# Examples
+
+```
+# fn main() {
+let x = 1;
+# }
+```
+
+# Panics
+
+If something goes wrong.
+
+## A sub-heading
+"#;
+        assert_yaml_snapshot!(DocSections::parse(docstring), @r###"
+        ---
+        summary: "The summary, over two lines."
+        body: The body.
+        sections:
+          Examples: "```\n# fn main() {\nlet x = 1;\n# }\n```"
+          Panics: "If something goes wrong.\n\n## A sub-heading"
+        "###);
+    }
+
+    #[test]
+    fn test_parse_sections_without_summary() {
+        assert_yaml_snapshot!(DocSections::parse("# Safety\n\nThe pointer must be valid."), @r###"
+        ---
+        summary: ""
+        body: ""
+        sections:
+          Safety: The pointer must be valid.
+        "###);
+    }
+
+    #[test]
+    fn test_check_safety_sections() {
+        let mut result = AnalysisResult::new(Crate {
+            name: "my_crate".into(),
+            version: "0.1.0".into(),
+            ..Default::default()
+        });
+        result.functions.push(Function::parse(
+            None,
+            &["my_crate"],
+            None,
+            &syn::parse_quote! {
+                /// Documented
+                ///
+                /// # Safety
+                ///
+                /// Always safe
+                pub unsafe fn documented() {}
+            },
+        ));
+        result.functions.push(Function::parse(
+            None,
+            &["my_crate"],
+            None,
+            &syn::parse_quote! {
+                /// Undocumented
+                pub unsafe fn undocumented() {}
+            },
+        ));
+        check_safety_sections(&mut result);
+        assert_yaml_snapshot!(result.diagnostics, @r###"
+        ---
+        - severity: warning
+          code: missing-safety-section
+          message: "unsafe function `my_crate::undocumented` has no `# Safety` section"
+          file: ~
+          item:
+            - my_crate
+            - undocumented
+          span:
+            file: ~
+            start_line: 1
+            start_column: 0
+            end_line: 1
+            end_column: 0
+        "###);
+    }
+}
@@ -1,9 +1,12 @@
 //! Analyze structs
-use syn::{ItemStruct, Visibility};
+use syn::ItemStruct;
 
-use crate::data_model::{Field, Struct};
+use crate::data_model::{CodeBlock, DocFormat, DocSections, Field, Struct, Visibility};
 
-use super::{docstring_from_attrs, type_::convert_type};
+use super::{
+    docstring::is_hidden, docstring_from_attrs, feature::cfg_features, span_from_ast,
+    type_::convert_type, AnalysisOptions,
+};
 
 impl Struct {
     /// Fully qualified name of the variant
@@ -11,22 +14,47 @@ impl Struct {
         self.path.join("::")
     }
     /// Extract the relevant information from the AST
-    pub fn parse(parent: &[&str], ast: &ItemStruct) -> Self {
+    ///
+    /// :param doc_format: The docstring format inherited from the parent module
+    /// :param options: The options selecting the fields to include
+    pub fn parse(
+        file: Option<&str>,
+        parent: &[&str],
+        doc_format: Option<DocFormat>,
+        ast: &ItemStruct,
+        options: &AnalysisOptions,
+    ) -> Self {
         let name = ast.ident.to_string();
         let path = parent
             .iter()
             .copied()
             .chain(Some(name.as_str()))
             .collect::<Vec<&str>>();
-        let docstring = docstring_from_attrs(&ast.attrs);
+        let (docstring, own_format) = docstring_from_attrs(&ast.attrs);
+        let sections = DocSections::parse(&docstring);
+        let doc_format = own_format.or(doc_format);
+        let code_blocks = CodeBlock::extract(&docstring, doc_format);
         let mut struct_ = Self {
             path: path.iter().map(|s| s.to_string()).collect(),
+            span: span_from_ast(file, ast),
             docstring,
+            doc_format,
+            links: vec![],
+            sections,
+            code_blocks,
+            source: None,
+            features: cfg_features(&ast.attrs),
             fields: vec![],
         };
         for (i, field) in ast.fields.iter().enumerate() {
-            if let Visibility::Public(_) = field.vis {
-                struct_.fields.push(Field::parse(&path, i, field));
+            if options.includes(
+                Visibility::from_ast(&field.vis),
+                is_hidden(&field.attrs),
+                &cfg_features(&field.attrs),
+            ) {
+                struct_
+                    .fields
+                    .push(Field::parse(file, &path, doc_format, i, field));
             }
         }
         struct_
@@ -35,7 +63,15 @@ impl Struct {
 
 impl Field {
     /// Extract the relevant information from the AST
-    pub fn parse(parent: &[&str], position: usize, ast: &syn::Field) -> Self {
+    ///
+    /// :param doc_format: The docstring format inherited from the parent item
+    pub fn parse(
+        file: Option<&str>,
+        parent: &[&str],
+        doc_format: Option<DocFormat>,
+        position: usize,
+        ast: &syn::Field,
+    ) -> Self {
         let name = ast
             .ident
             .as_ref()
@@ -46,11 +82,19 @@ impl Field {
             .copied()
             .chain(Some(name.as_str()))
             .collect::<Vec<&str>>();
-        let docstring = docstring_from_attrs(&ast.attrs);
+        let (docstring, own_format) = docstring_from_attrs(&ast.attrs);
+        let sections = DocSections::parse(&docstring);
+        let doc_format = own_format.or(doc_format);
+        let code_blocks = CodeBlock::extract(&docstring, doc_format);
         let type_ = convert_type(&ast.ty);
         Self {
             path: path.iter().map(|s| s.to_string()).collect(),
+            span: span_from_ast(file, ast),
             docstring,
+            doc_format,
+            links: vec![],
+            sections,
+            code_blocks,
             type_,
         }
     }
@@ -69,13 +113,28 @@ mod tests {
             /// docstring
             pub struct MyStruct;
         };
-        let struct_ = Struct::parse(&["crate"], &ast);
+        let struct_ = Struct::parse(None, &["crate"], None, &ast, &AnalysisOptions::default());
         assert_yaml_snapshot!(struct_, @r###"
         ---
         path:
           - crate
           - MyStruct
+        span:
+          file: ~
+          start_line: 1
+          start_column: 0
+          end_line: 1
+          end_column: 0
         docstring: "Multi-line\ndocstring"
+        doc_format: ~
+        links: []
+        sections:
+          summary: Multi-line docstring
+          body: ""
+          sections: {}
+        code_blocks: []
+        source: ~
+        features: []
         fields: []
         "###);
     }
@@ -92,23 +151,86 @@ mod tests {
                 other: String,
             }
         };
-        let struct_ = Struct::parse(&["crate"], &ast);
+        let struct_ = Struct::parse(None, &["crate"], None, &ast, &AnalysisOptions::default());
         assert_yaml_snapshot!(struct_, @r###"
         ---
         path:
           - crate
           - MyStruct
+        span:
+          file: ~
+          start_line: 1
+          start_column: 0
+          end_line: 1
+          end_column: 0
         docstring: "Multi-line\ndocstring"
+        doc_format: ~
+        links: []
+        sections:
+          summary: Multi-line docstring
+          body: ""
+          sections: {}
+        code_blocks: []
+        source: ~
+        features: []
         fields:
           - path:
               - crate
               - MyStruct
               - my_field
+            span:
+              file: ~
+              start_line: 1
+              start_column: 0
+              end_line: 1
+              end_column: 0
             docstring: Docstring
+            doc_format: ~
+            links: []
+            sections:
+              summary: Docstring
+              body: ""
+              sections: {}
+            code_blocks: []
             type_:
               - String: "["
               - Path: T
               - String: "; 1]"
         "###);
     }
+
+    #[test]
+    fn test_parse_struct_fields_visibility() {
+        let ast: ItemStruct = parse_quote! {
+            pub struct MyStruct {
+                pub a: u8,
+                pub(crate) b: u8,
+                c: u8,
+                #[doc(hidden)]
+                pub d: u8,
+                #[cfg(feature = "other")]
+                pub e: u8,
+            }
+        };
+        let field_names = |options: &AnalysisOptions| {
+            Struct::parse(None, &["crate"], None, &ast, options)
+                .fields
+                .iter()
+                .map(|field| field.path.last().unwrap().clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(field_names(&AnalysisOptions::default()), vec!["a", "e"]);
+        let options = AnalysisOptions {
+            visibility: Visibility::Crate,
+            features: Some(vec![]),
+            ..Default::default()
+        };
+        assert_eq!(field_names(&options), vec!["a", "b"]);
+        let options = AnalysisOptions {
+            visibility: Visibility::Private,
+            include_hidden: true,
+            ..Default::default()
+        };
+        assert_eq!(field_names(&options), vec!["a", "b", "c", "d", "e"]);
+    }
 }
@@ -0,0 +1,312 @@
+//! Discover the targets of a crate
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::data_model::{Target, TargetKind};
+
+use super::{
+    crate_::{relative_path, Package},
+    fs::FileSystem,
+};
+
+impl TargetKind {
+    /// The name of the kind, as used in ``Cargo.toml``, e.g. ``[[bin]]``
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TargetKind::Lib => "lib",
+            TargetKind::Bin => "bin",
+            TargetKind::Example => "example",
+            TargetKind::Test => "test",
+            TargetKind::Bench => "bench",
+        }
+    }
+
+    /// The directory, relative to the crate root, in which targets of this kind are discovered
+    fn directory(&self) -> &'static str {
+        match self {
+            TargetKind::Lib => "src",
+            TargetKind::Bin => "src/bin",
+            TargetKind::Example => "examples",
+            TargetKind::Test => "tests",
+            TargetKind::Bench => "benches",
+        }
+    }
+}
+
+/// Discover the targets of a crate, following cargo's rules
+///
+/// Targets declared in the ``Cargo.toml`` come first, followed by those discovered automatically
+/// (unless disabled with e.g. ``autobins = false``):
+/// ``src/lib.rs``, ``src/main.rs``, ``src/bin/*.rs``, ``examples/*.rs``, ``tests/*.rs``
+/// and ``benches/*.rs``, as well as ``*/main.rs`` in the latter four directories.
+///
+/// :param path: The crate directory
+pub(super) fn discover_targets(
+    fs: &dyn FileSystem,
+    path: &Path,
+    package: &Package,
+    declared: &TargetsToml,
+) -> Result<Vec<Target>> {
+    let mut targets: Vec<(String, TargetKind, String)> = vec![];
+
+    // the library
+    if let Some(lib) = &declared.lib {
+        targets.push((
+            lib.name
+                .clone()
+                .unwrap_or_else(|| package.name.replace('-', "_")),
+            TargetKind::Lib,
+            lib.path.clone().unwrap_or("src/lib.rs".to_string()),
+        ));
+    } else if package.autolib.unwrap_or(true) && fs.is_file(&path.join("src/lib.rs")) {
+        targets.push((
+            package.name.replace('-', "_"),
+            TargetKind::Lib,
+            "src/lib.rs".to_string(),
+        ));
+    }
+
+    for (kind, declared, auto) in [
+        (TargetKind::Bin, &declared.bin, package.autobins),
+        (TargetKind::Example, &declared.example, package.autoexamples),
+        (TargetKind::Test, &declared.test, package.autotests),
+        (TargetKind::Bench, &declared.bench, package.autobenches),
+    ] {
+        let inferred = infer_targets(fs, path, &package.name, kind);
+        let mut of_kind: Vec<(String, String)> = vec![];
+        for target in declared {
+            let Some(name) = &target.name else {
+                return Err(anyhow::anyhow!(format!(
+                    "A {} target has no name in: {}",
+                    kind.as_str(),
+                    path.join("Cargo.toml").to_string_lossy()
+                )));
+            };
+            let file = match &target.path {
+                Some(file) => file.clone(),
+                None => inferred
+                    .iter()
+                    .find(|(inferred_name, _)| inferred_name == name)
+                    .map(|(_, file)| file.clone())
+                    .unwrap_or_else(|| format!("{}/{}.rs", kind.directory(), name)),
+            };
+            of_kind.push((name.clone(), file));
+        }
+        if auto.unwrap_or(true) {
+            for (name, file) in inferred {
+                if !of_kind.iter().any(|(n, f)| *n == name || *f == file) {
+                    of_kind.push((name, file));
+                }
+            }
+        }
+        targets.extend(of_kind.into_iter().map(|(name, file)| (name, kind, file)));
+    }
+
+    // name the root module of each target uniquely
+    let mut result: Vec<Target> = vec![];
+    for (name, kind, file) in targets {
+        let base = name.replace('-', "_");
+        let taken = |module: &str| result.iter().any(|target| target.module == module);
+        let mut module = base.clone();
+        if taken(&module) {
+            module = format!("{}_{}", base, kind.as_str());
+        }
+        let mut number = 2;
+        while taken(&module) {
+            module = format!("{}_{}_{}", base, kind.as_str(), number);
+            number += 1;
+        }
+        result.push(Target {
+            name,
+            kind,
+            file,
+            module,
+        });
+    }
+    Ok(result)
+}
+
+/// Infer the targets of a kind from the files in the crate directory,
+/// returning the name and root module file (relative to the crate root) of each target
+fn infer_targets(
+    fs: &dyn FileSystem,
+    path: &Path,
+    package_name: &str,
+    kind: TargetKind,
+) -> Vec<(String, String)> {
+    let mut targets = vec![];
+    if kind == TargetKind::Bin && fs.is_file(&path.join("src/main.rs")) {
+        targets.push((package_name.to_string(), "src/main.rs".to_string()));
+    }
+    let Ok(entries) = fs.read_dir(&path.join(kind.directory())) else {
+        return targets;
+    };
+    let mut inferred = entries
+        .into_iter()
+        .filter_map(|entry| {
+            if fs.is_file(&entry) && entry.extension().is_some_and(|ext| ext == "rs") {
+                let name = entry.file_stem()?.to_string_lossy().to_string();
+                Some((name, relative_path(path, &entry)))
+            } else if fs.is_file(&entry.join("main.rs")) {
+                let name = entry.file_name()?.to_string_lossy().to_string();
+                Some((name, relative_path(path, &entry.join("main.rs"))))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    inferred.sort();
+    targets.extend(inferred);
+    targets
+}
+
+#[derive(Debug, Default, Deserialize)]
+/// The target sections of a ``Cargo.toml``
+pub(super) struct TargetsToml {
+    lib: Option<TargetToml>,
+    #[serde(default)]
+    bin: Vec<TargetToml>,
+    #[serde(default)]
+    example: Vec<TargetToml>,
+    #[serde(default)]
+    test: Vec<TargetToml>,
+    #[serde(default)]
+    bench: Vec<TargetToml>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TargetToml {
+    name: Option<String>,
+    path: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze::{crate_::CargoToml, fs::RealFileSystem};
+    use insta::assert_yaml_snapshot;
+
+    #[test]
+    fn test_discover_targets() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        for file in [
+            "src/lib.rs",
+            "src/main.rs",
+            "src/bin/tool.rs",
+            "src/bin/multi/main.rs",
+            "src/bin/custom_path.rs",
+            "examples/demo.rs",
+            "tests/integration.rs",
+            "benches/bench.rs",
+        ] {
+            let file = temp_dir.path().join(file);
+            std::fs::create_dir_all(file.parent().unwrap())?;
+            std::fs::write(file, "")?;
+        }
+        let cargo_toml: CargoToml = toml::from_str(
+            r#"
+            [package]
+            name = "my-crate"
+            autobenches = false
+
+            [[bin]]
+            name = "renamed"
+            path = "src/bin/custom_path.rs"
+
+            [[example]]
+            name = "demo"
+        "#,
+        )?;
+        let targets = discover_targets(
+            &RealFileSystem,
+            temp_dir.path(),
+            cargo_toml.package.as_ref().unwrap(),
+            &cargo_toml.targets,
+        )?;
+        assert_yaml_snapshot!(targets, @r###"
+        ---
+        - name: my_crate
+          kind: lib
+          file: src/lib.rs
+          module: my_crate
+        - name: renamed
+          kind: bin
+          file: src/bin/custom_path.rs
+          module: renamed
+        - name: my-crate
+          kind: bin
+          file: src/main.rs
+          module: my_crate_bin
+        - name: multi
+          kind: bin
+          file: src/bin/multi/main.rs
+          module: multi
+        - name: tool
+          kind: bin
+          file: src/bin/tool.rs
+          module: tool
+        - name: demo
+          kind: example
+          file: examples/demo.rs
+          module: demo
+        - name: integration
+          kind: test
+          file: tests/integration.rs
+          module: integration
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_discover_targets_colliding_modules() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        for file in [
+            "src/lib.rs",
+            "src/main.rs",
+            "src/bin/other.rs",
+            "examples/foo_bin.rs",
+        ] {
+            let file = temp_dir.path().join(file);
+            std::fs::create_dir_all(file.parent().unwrap())?;
+            std::fs::write(file, "")?;
+        }
+        let cargo_toml: CargoToml = toml::from_str(
+            r#"
+            [package]
+            name = "foo"
+
+            [[bin]]
+            name = "foo_bin"
+            path = "src/bin/other.rs"
+        "#,
+        )?;
+        let targets = discover_targets(
+            &RealFileSystem,
+            temp_dir.path(),
+            cargo_toml.package.as_ref().unwrap(),
+            &cargo_toml.targets,
+        )?;
+        assert_yaml_snapshot!(targets, @r###"
+        ---
+        - name: foo
+          kind: lib
+          file: src/lib.rs
+          module: foo
+        - name: foo_bin
+          kind: bin
+          file: src/bin/other.rs
+          module: foo_bin
+        - name: foo
+          kind: bin
+          file: src/main.rs
+          module: foo_bin_2
+        - name: foo_bin
+          kind: example
+          file: examples/foo_bin.rs
+          module: foo_bin_example
+        "###);
+        Ok(())
+    }
+}
@@ -23,6 +23,17 @@ impl From<String> for TypeSegment {
     }
 }
 
+impl TypeSegment {
+    /// The text of the segment, as written in the source code
+    pub fn text(&self) -> &str {
+        match self {
+            TypeSegment::String(text)
+            | TypeSegment::Path(text)
+            | TypeSegment::Resolved { text, .. } => text,
+        }
+    }
+}
+
 /// Converts a syn type to a list of text and Paths
 pub(super) fn convert_type(ty: &syn::Type) -> Vec<TypeSegment> {
     let mut v = convert_type_inner(ty);
@@ -0,0 +1,433 @@
+//! Analyze a Cargo workspace
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::data_model::DependencyKind;
+
+use super::{
+    crate_::{check_docstrings, read_crate, relative_path, AnalysisResult, Readme},
+    dependency::DependencyToml,
+    error::ParseError,
+    fs::{glob_dirs, FileSystem, RealFileSystem},
+    incremental::record_changes,
+    links::resolve_links_across,
+    AnalysisOptions,
+};
+
+/// Analyze all members of a workspace
+///
+/// Links in docstrings, and paths in types, are resolved across the members,
+/// for those members that are (path) dependencies of each other.
+///
+/// :param path: The path to the workspace directory, containing a ``Cargo.toml``
+///     with a ``[workspace]`` section
+pub fn analyze_workspace(path: &str, options: &AnalysisOptions) -> Result<WorkspaceResult> {
+    analyze_workspace_in(&RealFileSystem, path, options)
+}
+
+/// Analyze all members of a workspace, reading their files from the given file system
+///
+/// :param fs: The file system to read the workspace from
+/// :param path: The path to the workspace directory
+pub fn analyze_workspace_in(
+    fs: &dyn FileSystem,
+    path: &str,
+    options: &AnalysisOptions,
+) -> Result<WorkspaceResult> {
+    let root = fs
+        .canonicalize(Path::new(path))
+        .context(format!("Error resolving workspace path: {}", path))?;
+    let cargo_toml_path = root.join("Cargo.toml");
+    if !fs.exists(&cargo_toml_path) {
+        return Err(anyhow::anyhow!(format!(
+            "Cargo.toml does not exist in: {}",
+            root.to_string_lossy()
+        )));
+    }
+    let contents = fs.read_to_string(&cargo_toml_path)?;
+    let cargo_toml: WorkspaceToml = toml::from_str(&contents).context(format!(
+        "Error parsing: {}",
+        cargo_toml_path.to_string_lossy()
+    ))?;
+    let Some(workspace) = cargo_toml.workspace else {
+        return Err(anyhow::anyhow!(format!(
+            "No workspace section in: {}",
+            cargo_toml_path.to_string_lossy()
+        )));
+    };
+
+    // find the member directories, with the root package first, if there is one
+    let mut member_dirs = vec![];
+    if cargo_toml.package.is_some() {
+        member_dirs.push(root.clone());
+    }
+    let excluded = workspace
+        .exclude
+        .iter()
+        .filter_map(|p| fs.canonicalize(&root.join(p)).ok())
+        .collect::<Vec<_>>();
+    for pattern in &workspace.members {
+        let paths = glob_dirs(fs, &root, pattern)
+            .context(format!("Invalid workspace member: {}", pattern))?;
+        let mut paths = paths
+            .into_iter()
+            .filter(|p| fs.is_file(&p.join("Cargo.toml")))
+            .filter_map(|p| fs.canonicalize(&p).ok())
+            .collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            if !excluded.iter().any(|e| path.starts_with(e)) && !member_dirs.contains(&path) {
+                member_dirs.push(path);
+            }
+        }
+    }
+
+    let mut crates = vec![];
+    for dir in &member_dirs {
+        let result = read_crate(fs, &dir.to_string_lossy(), options).map_err(|mut err| {
+            // locate a parse error relative to the workspace root, rather than to the member
+            let member = relative_path(&root, dir);
+            if let Some(error) = err.downcast_mut::<ParseError>() {
+                if !member.is_empty() {
+                    error.span.file = error.span.file.take().map(|f| format!("{member}/{f}"));
+                }
+            }
+            err
+        })?;
+        crates.push(result);
+    }
+
+    // record the path dependencies between members, that can be referred to in code
+    let mut members = vec![];
+    for (dir, result) in member_dirs.iter().zip(&crates) {
+        let dependencies = result
+            .crate_
+            .dependencies
+            .iter()
+            .filter(|dependency| dependency.kind != DependencyKind::Build)
+            .filter_map(|dependency| {
+                let dep_dir = fs.canonicalize(&dir.join(dependency.path.as_ref()?)).ok()?;
+                let index = member_dirs.iter().position(|d| *d == dep_dir)?;
+                let crate_name = crates[index].crate_.name.clone();
+                let extern_name = if dependency.package.is_some() {
+                    dependency.name.replace('-', "_")
+                } else {
+                    crate_name.replace('-', "_")
+                };
+                Some((extern_name, crate_name))
+            })
+            .collect();
+        members.push(WorkspaceMember {
+            name: result.crate_.name.clone(),
+            path: relative_path(&root, dir),
+            dependencies,
+        });
+    }
+
+    let externs = members
+        .iter()
+        .map(|m| (m.name.clone(), m.dependencies.clone()))
+        .collect();
+    resolve_links_across(&mut crates, &externs);
+    for result in crates.iter_mut() {
+        check_docstrings(result);
+        record_changes(result, options);
+    }
+
+    Ok(WorkspaceResult { members, crates })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Result from a workspace analysis
+pub struct WorkspaceResult {
+    /// The members of the workspace, in the order they were found
+    pub members: Vec<WorkspaceMember>,
+    /// The analysis of each member, in the same order as the members
+    pub crates: Vec<AnalysisResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A member crate of a workspace
+pub struct WorkspaceMember {
+    /// The name of the crate
+    pub name: String,
+    /// The path to the crate directory, relative to the workspace root
+    pub path: String,
+    /// The dependencies of the crate that are also members of the workspace,
+    /// by the name they are referenced with in the crate -> crate name
+    pub dependencies: BTreeMap<String, String>,
+}
+
+/// Find the fields that a crate can inherit from the workspace it belongs to,
+/// by walking up from the crate directory to the first ``Cargo.toml`` with a ``[workspace]`` section
+///
+/// :param dir: The (canonical) crate directory
+pub(super) fn find_workspace_fields(
+    fs: &dyn FileSystem,
+    dir: &Path,
+) -> Result<Option<WorkspaceFields>> {
+    for ancestor in dir.ancestors() {
+        let cargo_toml_path = ancestor.join("Cargo.toml");
+        if !fs.is_file(&cargo_toml_path) {
+            continue;
+        }
+        let contents = fs.read_to_string(&cargo_toml_path)?;
+        let cargo_toml: WorkspaceToml = toml::from_str(&contents).context(format!(
+            "Error parsing: {}",
+            cargo_toml_path.to_string_lossy()
+        ))?;
+        let Some(workspace) = cargo_toml.workspace else {
+            continue;
+        };
+        // paths in the workspace manifest are relative to the workspace root
+        let to_root = "../".repeat(dir.strip_prefix(ancestor)?.components().count());
+        let mut package = workspace.package.unwrap_or_default();
+        if let Some(Readme::Path(readme)) = &mut package.readme {
+            *readme = format!("{to_root}{readme}");
+        }
+        let mut dependencies = workspace.dependencies;
+        for dependency in dependencies.values_mut() {
+            if let Some(path) = dependency.path() {
+                let path = format!("{to_root}{path}");
+                dependency.set_path(path);
+            }
+        }
+        return Ok(Some(WorkspaceFields {
+            package,
+            dependencies,
+        }));
+    }
+    Ok(None)
+}
+
+#[derive(Debug, Default)]
+/// The fields of a workspace that its members can inherit,
+/// with paths relative to the member directory
+pub(super) struct WorkspaceFields {
+    pub package: WorkspacePackage,
+    pub dependencies: BTreeMap<String, DependencyToml>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspaceToml {
+    workspace: Option<Workspace>,
+    package: Option<toml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Workspace {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    /// Package fields that members can inherit
+    package: Option<WorkspacePackage>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, DependencyToml>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+/// The ``[workspace.package]`` section, with the package fields that members can inherit
+pub(super) struct WorkspacePackage {
+    pub version: Option<String>,
+    pub edition: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub documentation: Option<String>,
+    pub readme: Option<Readme>,
+    pub keywords: Option<Vec<String>>,
+    pub categories: Option<Vec<String>>,
+    pub authors: Option<Vec<String>>,
+    pub rust_version: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_yaml_snapshot;
+
+    /// Write files to a directory, creating parent directories as needed
+    fn write_files(root: &Path, files: &[(&str, &str)]) -> Result<()> {
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, content)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_workspace() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        write_files(
+            temp_dir.path(),
+            &[
+                (
+                    "Cargo.toml",
+                    r#"
+                    [workspace]
+                    members = ["crates/*"]
+                    exclude = ["crates/excluded"]
+
+                    [workspace.dependencies]
+                    core_crate = { path = "crates/core" }
+                    "#,
+                ),
+                (
+                    "crates/core/Cargo.toml",
+                    r#"
+                    [package]
+                    name = "core_crate"
+                    version = "0.1.0"
+
+                    [lib]
+                    "#,
+                ),
+                (
+                    "crates/core/src/lib.rs",
+                    "/// A struct in the core crate\npub struct Shared;",
+                ),
+                (
+                    "crates/app/Cargo.toml",
+                    r#"
+                    [package]
+                    name = "app"
+                    version = "0.2.0"
+
+                    [lib]
+
+                    [dependencies]
+                    core_crate.workspace = true
+                    renamed = { path = "../core", package = "core_crate" }
+                    serde = "1.0"
+                    "#,
+                ),
+                (
+                    "crates/app/src/lib.rs",
+                    r#"
+                    //! <!-- doc-format: markdown -->
+                    //! Uses [`core_crate::Shared`] and [`renamed::Shared`]
+                    use core_crate::Shared;
+
+                    /// Returns a [`Shared`]
+                    pub fn make(shared: renamed::Shared) -> Shared {}
+                    "#,
+                ),
+                (
+                    "crates/excluded/Cargo.toml",
+                    "[package]\nname = \"excluded\"\nversion = \"0.1.0\"\n\n[lib]",
+                ),
+            ],
+        )?;
+
+        let result = analyze_workspace(
+            &temp_dir.path().to_string_lossy(),
+            &AnalysisOptions::default(),
+        )?;
+        assert_yaml_snapshot!(result.members, @r###"
+        ---
+        - name: app
+          path: crates/app
+          dependencies:
+            core_crate: core_crate
+            renamed: core_crate
+        - name: core_crate
+          path: crates/core
+          dependencies: {}
+        "###);
+        assert_yaml_snapshot!(result.crates[0].modules[0].links, @r###"
+        ---
+        - text: "core_crate::Shared"
+          target: "core_crate::Shared"
+          path:
+            - core_crate
+            - Shared
+          kind: struct
+        - text: "renamed::Shared"
+          target: "renamed::Shared"
+          path:
+            - core_crate
+            - Shared
+          kind: struct
+        "###);
+        assert_yaml_snapshot!(result.crates[0].functions[0].parameters, @r###"
+        ---
+        - name: shared
+          type_:
+            - Resolved:
+                text: "renamed::Shared"
+                path:
+                  - core_crate
+                  - Shared
+                kind: struct
+        "###);
+        assert_yaml_snapshot!(result.crates[0].functions[0].returns, @r###"
+        ---
+        - Resolved:
+            text: Shared
+            path:
+              - core_crate
+              - Shared
+            kind: struct
+        "###);
+        assert_yaml_snapshot!(result.crates[0].diagnostics, @r###"
+        ---
+        []
+        "###);
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_workspace_parse_error() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        write_files(
+            temp_dir.path(),
+            &[
+                ("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]"),
+                (
+                    "crates/a/Cargo.toml",
+                    "[package]\nname = \"a\"\nversion = \"0.1.0\"",
+                ),
+                ("crates/a/src/lib.rs", "pub struct Broken"),
+            ],
+        )?;
+        let options = AnalysisOptions {
+            strict: true,
+            ..Default::default()
+        };
+        let error = analyze_workspace(&temp_dir.path().to_string_lossy(), &options).unwrap_err();
+        let parse_error = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(
+            parse_error.span.file.as_deref(),
+            Some("crates/a/src/lib.rs")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_workspace_without_workspace_section() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        write_files(
+            temp_dir.path(),
+            &[(
+                "Cargo.toml",
+                "[package]\nname = \"my_crate\"\nversion = \"0.1.0\"",
+            )],
+        )?;
+        let result = analyze_workspace(
+            &temp_dir.path().to_string_lossy(),
+            &AnalysisOptions::default(),
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("No workspace section in:"));
+        Ok(())
+    }
+}
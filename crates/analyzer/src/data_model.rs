@@ -1,7 +1,9 @@
 //! Data model for the analyzer
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 /// Representation of a crate
 ///
 /// .. req:: Represent a crate
@@ -11,6 +13,200 @@ use serde::{Deserialize, Serialize};
 pub struct Crate {
     pub name: String,
     pub version: String,
+    /// The Rust edition of the crate, e.g. ``2021``
+    pub edition: Option<String>,
+    pub description: Option<String>,
+    /// The license of the crate, as an SPDX expression
+    pub license: Option<String>,
+    /// The URL of the source repository
+    pub repository: Option<String>,
+    /// The URL of the crate's homepage
+    pub homepage: Option<String>,
+    /// The URL of the crate's documentation
+    pub documentation: Option<String>,
+    /// The path to the readme file, relative to the crate root
+    pub readme: Option<String>,
+    pub keywords: Vec<String>,
+    /// The crates.io categories of the crate
+    pub categories: Vec<String>,
+    pub authors: Vec<String>,
+    /// The minimum supported Rust version
+    pub rust_version: Option<String>,
+    /// The targets of the crate, starting with the library, if there is one
+    pub targets: Vec<Target>,
+    /// The dependencies of the crate, of all kinds
+    pub dependencies: Vec<Dependency>,
+    /// The features of the crate, in the order they are declared,
+    /// followed by those implied by optional dependencies
+    pub features: Vec<Feature>,
+    /// The analysis configuration, from ``[package.metadata.sphinx-rust]``
+    pub config: CrateConfig,
+    /// Where the crate was read from, if it is a packaged crate, e.g. a dependency
+    pub source: Option<CrateSource>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The source of a packaged crate
+pub struct CrateSource {
+    pub kind: SourceKind,
+    /// The path to the archive or directory the crate was read from
+    pub path: String,
+    /// The name of the registry directory, for a crate in ``~/.cargo/registry/src``,
+    /// e.g. ``index.crates.io-6f17d22bba15001f``
+    pub registry: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The kind of source of a packaged crate
+pub enum SourceKind {
+    /// A ``.crate`` archive, as created by ``cargo package``
+    Archive,
+    /// A crate directory in the output of ``cargo vendor``
+    Vendor,
+    /// An extracted crate in the cargo registry, e.g. ``~/.cargo/registry/src``
+    Registry,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Configuration of the analysis of a crate,
+/// read from the ``[package.metadata.sphinx-rust]`` table of its ``Cargo.toml``
+pub struct CrateConfig {
+    /// Glob patterns of item paths to include, e.g. ``my_crate::api::*``;
+    /// if empty, all items are included
+    pub include: Vec<String>,
+    /// Glob patterns of item paths to exclude, which take precedence over ``include``
+    pub exclude: Vec<String>,
+    /// The minimum visibility of items to include
+    pub visibility: Visibility,
+    /// The features to consider enabled, or all features if not set
+    pub features: Option<Vec<String>>,
+    /// The default format of docstrings in the crate
+    pub doc_format: Option<DocFormat>,
+    /// Whether to include items marked with ``#[doc(hidden)]``
+    pub include_hidden: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The visibility of an item, from most to least visible
+pub enum Visibility {
+    /// ``pub``
+    #[default]
+    Public,
+    /// ``pub(crate)``, ``pub(super)`` or ``pub(in ...)``
+    Crate,
+    /// No visibility modifier
+    Private,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A feature of a crate, declared in the ``[features]`` table of the ``Cargo.toml``
+pub struct Feature {
+    pub name: String,
+    /// The documentation of the feature, from the ``## `` comment lines above it
+    pub docstring: String,
+    /// Whether the feature is enabled by default, directly or through other features
+    pub default: bool,
+    /// Whether the feature is implied by an optional dependency, rather than declared
+    pub implicit: bool,
+    /// The other features of the crate that the feature enables
+    pub features: Vec<String>,
+    /// The optional dependencies that the feature enables
+    pub dependencies: Vec<String>,
+    /// The features of dependencies that the feature enables, e.g. ``serde/derive``
+    pub dependency_features: Vec<String>,
+    /// The items that are gated on the feature, with ``#[cfg(feature = "...")]``
+    pub items: Vec<ItemRef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A reference to an analyzed item
+pub struct ItemRef {
+    /// The fully qualified name of the item
+    pub path: Vec<String>,
+    pub kind: ItemKind,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// The differences between the items of an incremental analysis of a crate,
+/// and those of its previous analysis
+pub struct Changes {
+    /// The items that were not in the previous analysis
+    pub added: Vec<ItemRef>,
+    /// The items that differ from the previous analysis, e.g. in their docstring or signature
+    pub changed: Vec<ItemRef>,
+    /// The items of the previous analysis that no longer exist
+    pub removed: Vec<ItemRef>,
+    /// The module files that were parsed again, rather than reused from the previous analysis,
+    /// relative to the crate root
+    pub parsed_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A dependency of a crate
+pub struct Dependency {
+    /// The name the dependency is referred to by in ``Cargo.toml``
+    pub name: String,
+    /// The name of the package, if the dependency is renamed
+    pub package: Option<String>,
+    pub kind: DependencyKind,
+    /// The version requirement, e.g. ``1.0``
+    pub version: Option<String>,
+    /// The path to a local dependency, relative to the crate root
+    pub path: Option<String>,
+    /// The URL of a git dependency
+    pub git: Option<String>,
+    /// The features enabled for the dependency
+    pub features: Vec<String>,
+    /// Whether the default features of the dependency are enabled
+    pub default_features: bool,
+    /// Whether the dependency is optional, i.e. enabled by a feature
+    pub optional: bool,
+    /// The platform the dependency is specific to, e.g. ``cfg(windows)``
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The kind of a dependency, by the section it is declared in
+pub enum DependencyKind {
+    /// ``[dependencies]``
+    Normal,
+    /// ``[dev-dependencies]``
+    Dev,
+    /// ``[build-dependencies]``
+    Build,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A target of a crate, i.e. a library, binary, example, test or benchmark
+///
+/// Each target is analyzed as its own tree of modules
+pub struct Target {
+    /// The name of the target, as given to cargo, e.g. ``cargo run --bin <name>``
+    pub name: String,
+    pub kind: TargetKind,
+    /// The path to the root module file, relative to the crate root
+    pub file: String,
+    /// The name of the root module of the target,
+    /// i.e. the first component of the fully qualified names of its items.
+    ///
+    /// This is the crate name given to the compiler (with ``-`` replaced by ``_``),
+    /// or ``<name>_<kind>``, if that is already taken by another target of the crate,
+    /// followed by ``_2``, ``_3``, etc. until it is unique.
+    pub module: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The kind of a crate target
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Example,
+    Test,
+    Bench,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,13 +217,41 @@ pub struct Crate {
 ///     :tags: rust
 ///     :status: in-progress
 pub struct Module {
-    /// The path to the module file
+    /// The path to the module file, relative to the crate root
     pub file: Option<String>,
     /// The fully qualified name of the module
     pub path: Vec<String>,
     pub docstring: String,
-    /// The public declarations in the module
+    /// The format of the docstring, if specified
+    pub doc_format: Option<DocFormat>,
+    /// The links to other items in the docstring
+    pub links: Vec<DocLink>,
+    /// The docstring, split into its summary, body and sections
+    pub sections: DocSections,
+    /// The fenced code blocks in the docstring, e.g. doctests
+    pub code_blocks: Vec<CodeBlock>,
+    /// The features that the module is gated on, with ``#[cfg(feature = "...")]``
+    pub features: Vec<String>,
+    /// The public declarations of sub-modules in their own files, e.g. ``mod name;``
     pub declarations: Vec<String>,
+    /// The features that each public sub-module declaration is gated on,
+    /// which is only needed during the analysis
+    #[serde(skip)]
+    pub declaration_features: BTreeMap<String, Vec<String>>,
+    /// The location of each public sub-module declaration,
+    /// which is only needed during the analysis
+    #[serde(skip)]
+    pub declaration_spans: BTreeMap<String, Span>,
+    /// The public modules declared inline in the module file, e.g. ``mod name { ... }``,
+    /// and recursively within them, which are moved to the analysis result
+    #[serde(skip)]
+    pub inline_modules: Vec<Module>,
+    /// The problems found while parsing the module and its items,
+    /// which are moved to the analysis result
+    #[serde(skip)]
+    pub diagnostics: Vec<Diagnostic>,
+    /// The ``use`` declarations in the module, public or not
+    pub imports: Vec<Import>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,8 +264,22 @@ pub struct Module {
 pub struct Struct {
     /// The fully qualified name of the struct
     pub path: Vec<String>,
+    /// The location of the struct in the source code
+    pub span: Span,
     /// The docstring of the struct
     pub docstring: String,
+    /// The format of the docstring, if specified
+    pub doc_format: Option<DocFormat>,
+    /// The links to other items in the docstring
+    pub links: Vec<DocLink>,
+    /// The docstring, split into its summary, body and sections
+    pub sections: DocSections,
+    /// The fenced code blocks in the docstring, e.g. doctests
+    pub code_blocks: Vec<CodeBlock>,
+    /// The source code of the struct, as written in the module file
+    pub source: Option<String>,
+    /// The features that the struct is gated on, with ``#[cfg(feature = "...")]``
+    pub features: Vec<String>,
     pub fields: Vec<Field>,
 }
 
@@ -55,8 +293,22 @@ pub struct Struct {
 pub struct Enum {
     /// The fully qualified name of the enum
     pub path: Vec<String>,
+    /// The location of the enum in the source code
+    pub span: Span,
     /// The docstring of the enum
     pub docstring: String,
+    /// The format of the docstring, if specified
+    pub doc_format: Option<DocFormat>,
+    /// The links to other items in the docstring
+    pub links: Vec<DocLink>,
+    /// The docstring, split into its summary, body and sections
+    pub sections: DocSections,
+    /// The fenced code blocks in the docstring, e.g. doctests
+    pub code_blocks: Vec<CodeBlock>,
+    /// The source code of the enum, as written in the module file
+    pub source: Option<String>,
+    /// The features that the enum is gated on, with ``#[cfg(feature = "...")]``
+    pub features: Vec<String>,
     pub variants: Vec<Variant>,
 }
 
@@ -65,8 +317,18 @@ pub struct Enum {
 pub struct Variant {
     /// The fully qualified name of the variant
     pub path: Vec<String>,
+    /// The location of the variant in the source code
+    pub span: Span,
     /// The docstring of the variant
     pub docstring: String,
+    /// The format of the docstring, if specified
+    pub doc_format: Option<DocFormat>,
+    /// The links to other items in the docstring
+    pub links: Vec<DocLink>,
+    /// The docstring, split into its summary, body and sections
+    pub sections: DocSections,
+    /// The fenced code blocks in the docstring, e.g. doctests
+    pub code_blocks: Vec<CodeBlock>,
     pub discriminant: Option<String>, // TODO shouldn't just be a string
     pub fields: Vec<Field>,
 }
@@ -78,8 +340,18 @@ pub struct Field {
     ///
     /// Note, for fields of tuple structs, the final component is the index of the field
     pub path: Vec<String>,
+    /// The location of the field in the source code
+    pub span: Span,
     /// The docstring of the field
     pub docstring: String,
+    /// The format of the docstring, if specified
+    pub doc_format: Option<DocFormat>,
+    /// The links to other items in the docstring
+    pub links: Vec<DocLink>,
+    /// The docstring, split into its summary, body and sections
+    pub sections: DocSections,
+    /// The fenced code blocks in the docstring, e.g. doctests
+    pub code_blocks: Vec<CodeBlock>,
     pub type_: TypeSignature,
 }
 
@@ -88,9 +360,164 @@ pub struct Field {
 pub struct Function {
     /// The fully qualified name of the function.
     pub path: Vec<String>,
+    /// The location of the function in the source code
+    pub span: Span,
     /// The docstring of the function
     pub docstring: String,
-    // TODO signature
+    /// The format of the docstring, if specified
+    pub doc_format: Option<DocFormat>,
+    /// The links to other items in the docstring
+    pub links: Vec<DocLink>,
+    /// The docstring, split into its summary, body and sections
+    pub sections: DocSections,
+    /// The fenced code blocks in the docstring, e.g. doctests
+    pub code_blocks: Vec<CodeBlock>,
+    /// The source code of the function, as written in the module file
+    pub source: Option<String>,
+    /// The features that the function is gated on, with ``#[cfg(feature = "...")]``
+    pub features: Vec<String>,
+    /// Whether the function is declared ``unsafe``
+    pub is_unsafe: bool,
+    /// The parameters of the function
+    pub parameters: Vec<Parameter>,
+    /// The return type of the function, if it is not ``()``
+    pub returns: Option<TypeSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Representation of a function parameter
+pub struct Parameter {
+    /// The name of the parameter, or its pattern if it is not a single identifier
+    pub name: String,
+    pub type_: TypeSignature,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The format of a docstring
+///
+/// The serialized names match those of the Sphinx source parsers,
+/// except for ``rustdoc``, which is converted to reStructuredText before parsing.
+pub enum DocFormat {
+    Restructuredtext,
+    Markdown,
+    /// rustdoc-flavoured Markdown
+    Rustdoc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The kind of an analyzed item
+pub enum ItemKind {
+    Module,
+    Struct,
+    Enum,
+    Variant,
+    Field,
+    Function,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A link in a docstring to another item, e.g. ``[`Struct`]`` or ``[text](crate::module)``
+pub struct DocLink {
+    /// The text of the link
+    pub text: String,
+    /// The target of the link, as written in the docstring
+    pub target: String,
+    /// The fully qualified name of the item that the target resolves to
+    pub path: Option<Vec<String>>,
+    /// The kind of the item that the target resolves to
+    pub kind: Option<ItemKind>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A name brought into scope by a ``use`` declaration
+pub struct Import {
+    /// The name in scope, or ``*`` for a glob import
+    pub name: String,
+    /// The path of the imported item (or module, for a glob import).
+    ///
+    /// ``crate``, ``self`` and ``super`` are replaced by the path they refer to.
+    pub path: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// A docstring split into parts, following the Rust convention of
+/// a summary paragraph, followed by a body and ``# Heading`` sections,
+/// such as ``# Examples``, ``# Panics``, ``# Errors`` and ``# Safety``
+pub struct DocSections {
+    /// The first paragraph of the docstring
+    pub summary: String,
+    /// The rest of the docstring, before the first section
+    pub body: String,
+    /// The content of each section, by its heading, e.g. ``Examples``
+    pub sections: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A fenced code block in a docstring, e.g. a doctest
+pub struct CodeBlock {
+    /// The line of the opening fence, relative to the start of the docstring (1-based)
+    pub line: usize,
+    /// The language of the block, which is ``rust`` if it only has rustdoc attributes
+    pub lang: String,
+    /// The rustdoc attributes of the block, e.g. ``ignore``, ``no_run`` or ``should_panic``
+    pub attributes: Vec<String>,
+    /// The lines of the block
+    pub lines: Vec<CodeLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A line of a code block
+pub struct CodeLine {
+    /// The text of the line, with any ``# `` prefix of hidden lines removed
+    pub text: String,
+    /// Whether the line is hidden in the documentation, but still part of the doctest
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A problem found during the analysis
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// An identifier for the type of problem, e.g. ``unresolved-link``
+    pub code: String,
+    /// A description of the problem
+    pub message: String,
+    /// The path to the file that the problem is in, relative to the crate root
+    pub file: Option<String>,
+    /// The fully qualified name of the item that the problem relates to
+    pub item: Vec<String>,
+    /// The location of the problem in the source code
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The severity of a diagnostic
+pub enum Severity {
+    /// Something that may be intended, but is worth knowing about
+    Info,
+    /// Something that is probably wrong, e.g. an unresolved link
+    Warning,
+    /// Something that prevented part of the crate from being analyzed
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The location of an item in the source code
+pub struct Span {
+    /// The path to the file, relative to the crate root
+    pub file: Option<String>,
+    /// The line on which the item starts (1-based)
+    pub start_line: usize,
+    /// The column at which the item starts (0-based, in characters)
+    pub start_column: usize,
+    /// The line on which the item ends (1-based)
+    pub end_line: usize,
+    /// The column at which the item ends (0-based, in characters)
+    pub end_column: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +527,14 @@ pub struct Function {
 pub enum TypeSegment {
     String(String),
     Path(String),
+    /// A path that resolves to an analyzed struct or enum
+    Resolved {
+        /// The path, as written in the source code
+        text: String,
+        /// The fully qualified name of the item
+        path: Vec<String>,
+        kind: ItemKind,
+    },
 }
 
 /// A representation of a type signature
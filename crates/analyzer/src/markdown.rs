@@ -0,0 +1,637 @@
+//! Convert rustdoc-flavoured Markdown to reStructuredText
+//!
+//! This supports the Markdown extensions that rustdoc enables:
+//! tables, footnotes, strikethrough, task lists and intra-doc links.
+//! Headings are demoted to rubrics, since docstrings cannot contain sections,
+//! and resolved intra-doc links are converted to ``:rust:`` roles.
+use pulldown_cmark::{BrokenLink, CodeBlockKind, CowStr, Event, LinkType, Options, Parser, Tag};
+
+use crate::analyze::links::is_item_path;
+use crate::data_model::{CodeBlock, DocLink, ItemKind};
+
+/// The indentation of directive content
+const INDENT: &str = "   ";
+
+/// A node of the Markdown syntax tree
+enum Node<'a> {
+    /// A container, e.g. a paragraph, with its children
+    Tag(Tag<'a>, Vec<Node<'a>>),
+    /// A leaf, e.g. text
+    Leaf(Event<'a>),
+}
+
+/// Build a syntax tree from the events of the parser
+fn build_tree<'a>(events: impl Iterator<Item = Event<'a>>) -> Vec<Node<'a>> {
+    let mut stack: Vec<(Option<Tag<'a>>, Vec<Node<'a>>)> = vec![(None, vec![])];
+    for event in events {
+        match event {
+            Event::Start(tag) => stack.push((Some(tag), vec![])),
+            Event::End(_) => {
+                if let Some((Some(tag), children)) = stack.pop() {
+                    if let Some((_, parent)) = stack.last_mut() {
+                        parent.push(Node::Tag(tag, children));
+                    }
+                }
+            }
+            event => {
+                if let Some((_, parent)) = stack.last_mut() {
+                    parent.push(Node::Leaf(event));
+                }
+            }
+        }
+    }
+    stack.pop().map(|(_, nodes)| nodes).unwrap_or_default()
+}
+
+/// Convert a Markdown docstring to reStructuredText
+///
+/// :param markdown: The Markdown text
+/// :param links: The links of the docstring, with their resolved targets
+pub fn markdown_to_rst(markdown: &str, links: &[DocLink]) -> String {
+    let mut callback = |link: BrokenLink<'_>| {
+        let reference = link.reference.to_string();
+        is_item_path(&reference).then(|| (CowStr::from(reference), CowStr::Borrowed("")))
+    };
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_with_broken_link_callback(markdown, options, Some(&mut callback));
+    let tree = build_tree(parser);
+    let mut converter = Converter {
+        links,
+        strikethrough: false,
+    };
+    let mut lines = converter.blocks(&tree);
+    if converter.strikethrough {
+        lines.splice(0..0, [".. role:: del".to_string(), String::new()]);
+    }
+    lines.join("\n")
+}
+
+/// Converts a Markdown syntax tree to reStructuredText lines
+struct Converter<'a> {
+    links: &'a [DocLink],
+    /// Whether the strikethrough role is used, and so needs to be declared
+    strikethrough: bool,
+}
+
+impl Converter<'_> {
+    /// Convert a sequence of block nodes, separated by blank lines
+    fn blocks(&mut self, nodes: &[Node]) -> Vec<String> {
+        let mut blocks: Vec<Vec<String>> = vec![];
+        // consecutive inline nodes, e.g. in tight list items, form a paragraph
+        let mut inline: Vec<&Node> = vec![];
+        for node in nodes {
+            if is_inline(node) {
+                inline.push(node);
+                continue;
+            }
+            if !inline.is_empty() {
+                blocks.push(self.paragraph(&inline));
+                inline.clear();
+            }
+            let block = self.block(node);
+            if !block.is_empty() {
+                blocks.push(block);
+            }
+        }
+        if !inline.is_empty() {
+            blocks.push(self.paragraph(&inline));
+        }
+        // transitions are not allowed at the start or end of the document
+        while blocks.first().is_some_and(|b| is_transition(b)) {
+            blocks.remove(0);
+        }
+        while blocks.last().is_some_and(|b| is_transition(b)) {
+            blocks.pop();
+        }
+        join_blocks(blocks)
+    }
+
+    /// Convert a single block node
+    fn block(&mut self, node: &Node) -> Vec<String> {
+        let Node::Tag(tag, children) = node else {
+            return match node {
+                Node::Leaf(Event::Rule) => vec!["----".to_string()],
+                Node::Leaf(Event::Html(html)) => directive("raw", "html", &[], html.lines()),
+                _ => vec![],
+            };
+        };
+        match tag {
+            Tag::Paragraph => {
+                // an image on its own becomes an image directive
+                if let [Node::Tag(
+                    Tag::Image {
+                        dest_url, title, ..
+                    },
+                    alt,
+                )] = &children[..]
+                {
+                    let mut options = vec![format!(":alt: {}", plain_text(alt))];
+                    if !title.is_empty() {
+                        options.push(format!(":title: {}", title));
+                    }
+                    return directive("image", dest_url, &options, std::iter::empty());
+                }
+                self.paragraph(&children.iter().collect::<Vec<_>>())
+            }
+            Tag::Heading { .. } => {
+                let text = self.inline(children).replace('\n', " ");
+                directive("rubric", &text, &[], std::iter::empty())
+            }
+            Tag::BlockQuote(_) => {
+                // an empty comment ends any preceding construct, e.g. a list,
+                // so that the quote is not parsed as a continuation of it
+                let mut lines = vec!["..".to_string(), String::new()];
+                lines.extend(indent(self.blocks(children), "    "));
+                lines
+            }
+            Tag::CodeBlock(kind) => {
+                let content = plain_text(children);
+                let block = match kind {
+                    CodeBlockKind::Fenced(info) => CodeBlock::new(0, info),
+                    CodeBlockKind::Indented => CodeBlock::new(0, ""),
+                }
+                .with_content(&content);
+                let source = block.visible_source();
+                if source.trim().is_empty() {
+                    return vec![];
+                }
+                directive("code-block", &block.lang, &[], source.lines())
+            }
+            Tag::List(start) => {
+                let mut number = *start;
+                let items = children
+                    .iter()
+                    .map(|item| {
+                        let marker = match number.as_mut() {
+                            Some(n) => {
+                                *n += 1;
+                                format!("{}. ", *n - 1)
+                            }
+                            None => "- ".to_string(),
+                        };
+                        let content = match item {
+                            Node::Tag(Tag::Item, children) => self.blocks(children),
+                            node => self.block(node),
+                        };
+                        prefix_lines(content, &marker)
+                    })
+                    .collect();
+                join_blocks(items)
+            }
+            Tag::FootnoteDefinition(label) => {
+                let mut lines = vec![format!(".. [#{}]", footnote_label(label))];
+                lines.extend(indent(self.blocks(children), INDENT));
+                lines
+            }
+            Tag::Table(_) => self.table(children),
+            Tag::HtmlBlock => {
+                let html = plain_text(children);
+                directive("raw", "html", &[], html.lines())
+            }
+            // e.g. definition lists and metadata blocks, which rustdoc does not enable
+            _ => self.blocks(children),
+        }
+    }
+
+    /// Convert a table, to a ``list-table`` directive
+    fn table(&mut self, rows: &[Node]) -> Vec<String> {
+        let mut options = vec![];
+        let mut content = vec![];
+        for row in rows {
+            let (is_header, cells) = match row {
+                Node::Tag(Tag::TableHead, cells) => (true, cells),
+                Node::Tag(Tag::TableRow, cells) => (false, cells),
+                _ => continue,
+            };
+            if is_header {
+                options.push(":header-rows: 1".to_string());
+            }
+            for (i, cell) in cells.iter().enumerate() {
+                let text = match cell {
+                    Node::Tag(Tag::TableCell, children) => self.inline(children),
+                    _ => continue,
+                };
+                let marker = if i == 0 { "* - " } else { "  - " };
+                content.extend(prefix_lines(
+                    text.lines().map(|l| l.to_string()).collect(),
+                    marker,
+                ));
+            }
+        }
+        directive(
+            "list-table",
+            "",
+            &options,
+            content.iter().map(|s| s.as_str()),
+        )
+    }
+
+    /// Convert a paragraph of inline nodes
+    fn paragraph(&mut self, nodes: &[&Node]) -> Vec<String> {
+        let mut writer = InlineWriter::default();
+        for node in nodes {
+            self.write_inline(&mut writer, node);
+        }
+        let mut lines = writer
+            .buffer
+            .lines()
+            .map(|l| l.to_string())
+            .collect::<Vec<_>>();
+        // escape text that would otherwise start a list or directive
+        if let Some(first) = lines.first_mut() {
+            let starts_list = first.starts_with("- ")
+                || first.starts_with("+ ")
+                || first
+                    .split_once(['.', ')'])
+                    .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+            if starts_list || first.starts_with("..") {
+                first.insert(0, '\\');
+            }
+        }
+        lines
+    }
+
+    /// Convert a sequence of inline nodes
+    fn inline(&mut self, nodes: &[Node]) -> String {
+        let mut writer = InlineWriter::default();
+        for node in nodes {
+            self.write_inline(&mut writer, node);
+        }
+        writer.buffer
+    }
+
+    fn write_inline(&mut self, writer: &mut InlineWriter, node: &Node) {
+        match node {
+            Node::Leaf(event) => match event {
+                Event::Text(text) => writer.text(text),
+                Event::Code(code) => writer.markup(&literal(code)),
+                Event::InlineHtml(html) | Event::Html(html) => writer.text(html),
+                Event::SoftBreak | Event::HardBreak => writer.text("\n"),
+                Event::FootnoteReference(label) => {
+                    writer.markup(&format!("[#{}]_", footnote_label(label)))
+                }
+                Event::TaskListMarker(checked) => {
+                    writer.text(if *checked { "[x] " } else { "[ ] " })
+                }
+                Event::InlineMath(math) | Event::DisplayMath(math) => {
+                    writer.markup(&format!(":math:`{}`", escape_role(math)))
+                }
+                _ => {}
+            },
+            Node::Tag(tag, children) => match tag {
+                Tag::Emphasis => writer.markup(&format!("*{}*", escape(&plain_text(children)))),
+                Tag::Strong => writer.markup(&format!("**{}**", escape(&plain_text(children)))),
+                Tag::Strikethrough => {
+                    self.strikethrough = true;
+                    writer.markup(&format!(":del:`{}`", escape_role(&plain_text(children))))
+                }
+                Tag::Link {
+                    link_type,
+                    dest_url,
+                    ..
+                } => self.write_link(writer, *link_type, dest_url, children),
+                Tag::Image { dest_url, .. } => writer.markup(&format!(
+                    "`{} <{}>`__",
+                    escape_role(&plain_text(children)),
+                    dest_url
+                )),
+                _ => {
+                    for child in children {
+                        self.write_inline(writer, child);
+                    }
+                }
+            },
+        }
+    }
+
+    fn write_link(
+        &mut self,
+        writer: &mut InlineWriter,
+        link_type: LinkType,
+        dest_url: &str,
+        children: &[Node],
+    ) {
+        let text = plain_text(children);
+        let target = dest_url.trim().trim_matches('`');
+        if link_type == LinkType::Email {
+            writer.markup(&format!("`{} <mailto:{}>`__", escape_role(&text), target));
+        } else if is_item_path(target) {
+            let resolved = self
+                .links
+                .iter()
+                .find(|link| link.target == target)
+                .and_then(|link| Some((link.path.as_ref()?, link.kind?)));
+            match resolved {
+                Some((path, kind)) => writer.markup(&rust_role(&text, path, kind)),
+                // unresolved links are rendered as their text
+                None => {
+                    for child in children {
+                        self.write_inline(writer, child);
+                    }
+                }
+            }
+        } else if target.starts_with('#') || target.is_empty() {
+            // links to anchors within the page cannot be resolved
+            for child in children {
+                self.write_inline(writer, child);
+            }
+        } else {
+            writer.markup(&format!("`{} <{}>`__", escape_role(&text), target));
+        }
+    }
+}
+
+/// Create a role referencing an item of the ``rust`` domain
+///
+/// Variants and fields do not have their own objects in the domain,
+/// so they reference their parent item instead.
+fn rust_role(text: &str, path: &[String], kind: ItemKind) -> String {
+    let (role, path) = match kind {
+        ItemKind::Variant => ("rust:enum".to_string(), &path[..path.len() - 1]),
+        ItemKind::Field => ("any".to_string(), &path[..path.len() - 1]),
+        kind => (format!("rust:{}", kind.as_str()), path),
+    };
+    format!(":{}:`{} <{}>`", role, escape_role(text), path.join("::"))
+}
+
+/// Writes inline text, inserting escaped whitespace where needed
+/// so that inline markup is recognised when it is adjacent to other text
+#[derive(Default)]
+struct InlineWriter {
+    buffer: String,
+    after_markup: bool,
+}
+
+impl InlineWriter {
+    fn text(&mut self, text: &str) {
+        if self.after_markup
+            && text
+                .chars()
+                .next()
+                .is_some_and(|c| !c.is_whitespace() && !"-.,:;!?\\/'\")]}>".contains(c))
+        {
+            self.buffer.push_str("\\ ");
+        }
+        self.after_markup = false;
+        self.buffer.push_str(&escape(text));
+    }
+
+    fn markup(&mut self, markup: &str) {
+        if self
+            .buffer
+            .chars()
+            .last()
+            .is_some_and(|c| !c.is_whitespace() && !"-:/'\"<([{".contains(c))
+        {
+            self.buffer.push_str("\\ ");
+        }
+        self.buffer.push_str(markup);
+        self.after_markup = true;
+    }
+}
+
+/// Whether a node is inline content, rather than a block
+fn is_inline(node: &Node) -> bool {
+    match node {
+        Node::Leaf(event) => !matches!(event, Event::Rule | Event::Html(_)),
+        Node::Tag(tag, _) => matches!(
+            tag,
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }
+        ),
+    }
+}
+
+fn is_transition(block: &[String]) -> bool {
+    block == ["----"]
+}
+
+/// The text content of nodes, without any markup
+fn plain_text(nodes: &[Node]) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            Node::Leaf(Event::Text(t) | Event::Code(t) | Event::Html(t) | Event::InlineHtml(t)) => {
+                text.push_str(t)
+            }
+            Node::Leaf(Event::SoftBreak | Event::HardBreak) => text.push(' '),
+            Node::Tag(_, children) => text.push_str(&plain_text(children)),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Escape characters that start inline markup
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '`' | '_' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape the content of an interpreted text role
+fn escape_role(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace('<', "\\<")
+        .replace('\n', " ")
+}
+
+/// Create an inline literal
+fn literal(code: &str) -> String {
+    if code.contains("``") || code.starts_with(' ') || code.ends_with(' ') || code.is_empty() {
+        format!(":code:`{}`", escape_role(code))
+    } else {
+        format!("``{}``", code)
+    }
+}
+
+/// Footnote labels may only contain alphanumerics and isolated hyphens, underscores and periods
+fn footnote_label(label: &str) -> String {
+    let label = label
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    label
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Create a directive with an argument, options and content
+fn directive<'a>(
+    name: &str,
+    argument: &str,
+    options: &[String],
+    content: impl Iterator<Item = &'a str>,
+) -> Vec<String> {
+    let mut lines = vec![format!(".. {}:: {}", name, argument).trim_end().to_string()];
+    lines.extend(options.iter().map(|o| format!("{INDENT}{o}")));
+    let content = content.map(|l| l.to_string()).collect::<Vec<_>>();
+    if !content.is_empty() {
+        lines.push(String::new());
+        lines.extend(indent(content, INDENT));
+    }
+    lines
+}
+
+/// Indent non-blank lines
+fn indent(lines: Vec<String>, prefix: &str) -> Vec<String> {
+    lines
+        .into_iter()
+        .map(|l| {
+            if l.trim().is_empty() {
+                String::new()
+            } else {
+                format!("{prefix}{l}")
+            }
+        })
+        .collect()
+}
+
+/// Prefix the first line, e.g. with a list marker, and indent the rest to align with it
+fn prefix_lines(lines: Vec<String>, marker: &str) -> Vec<String> {
+    let continuation = " ".repeat(marker.chars().count());
+    let mut lines = indent(lines, &continuation);
+    match lines.first_mut() {
+        Some(first) => *first = format!("{}{}", marker, first.trim_start()),
+        None => lines.push(marker.trim_end().to_string()),
+    }
+    lines
+}
+
+/// Join blocks of lines, separated by blank lines
+fn join_blocks(blocks: Vec<Vec<String>>) -> Vec<String> {
+    let mut lines = vec![];
+    for (i, block) in blocks.into_iter().enumerate() {
+        if i > 0 {
+            lines.push(String::new());
+        }
+        lines.extend(block);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn test_inline() {
+        assert_snapshot!(markdown_to_rst(
+            "Some *emphasis*, **strong**, `code`, ~~deleted~~, snake_case, a*b and [a link](https://example.com).\nA second line with <b>html</b>.",
+            &[]
+        ), @r###"
+        .. role:: del
+
+        Some *emphasis*, **strong**, ``code``, :del:`deleted`, snake\_case, a\*b and `a link <https://example.com>`__.
+        A second line with <b>html</b>.
+        "###);
+    }
+
+    #[test]
+    fn test_headings_and_code() {
+        assert_snapshot!(markdown_to_rst(
+            "# Examples\n\n```\n# use my_crate::Struct;\nlet x = Struct::new();\n```\n\n## Sub-heading\n\n```text\n# not hidden\n```",
+            &[]
+        ), @r###"
+        .. rubric:: Examples
+
+        .. code-block:: rust
+
+           let x = Struct::new();
+
+        .. rubric:: Sub-heading
+
+        .. code-block:: text
+
+           # not hidden
+        "###);
+    }
+
+    #[test]
+    fn test_lists_and_quotes() {
+        assert_snapshot!(markdown_to_rst(
+            "- item 1\n- item 2\n  - nested\n\n1. first\n2. second\n   continued\n\n- [x] done\n- [ ] todo\n\n> quoted\n> text\n\n---\n\n1\\. not a list",
+            &[]
+        ), @r###"
+        - item 1
+
+        - item 2
+
+          - nested
+
+        1. first
+
+        2. second
+           continued
+
+        - [x] done
+
+        - [ ] todo
+
+        ..
+
+            quoted
+            text
+
+        ----
+
+        \1. not a list
+        "###);
+    }
+
+    #[test]
+    fn test_tables_and_footnotes() {
+        assert_snapshot!(markdown_to_rst(
+            "| a | b |\n|---|:-:|\n| `1` | 2 |\n\nText with a footnote[^note].\n\n[^note]: The footnote.",
+            &[]
+        ), @r###"
+        .. list-table::
+           :header-rows: 1
+
+           * - a
+             - b
+           * - ``1``
+             - 2
+
+        Text with a footnote\ [#note]_.
+
+        .. [#note]
+           The footnote.
+        "###);
+    }
+
+    #[test]
+    fn test_links() {
+        let links = [
+            DocLink {
+                text: "Struct".into(),
+                target: "Struct".into(),
+                path: Some(vec!["my_crate".into(), "Struct".into()]),
+                kind: Some(ItemKind::Struct),
+            },
+            DocLink {
+                text: "variant".into(),
+                target: "Enum::Variant".into(),
+                path: Some(vec!["my_crate".into(), "Enum".into(), "Variant".into()]),
+                kind: Some(ItemKind::Variant),
+            },
+        ];
+        assert_snapshot!(markdown_to_rst(
+            "See [`Struct`], [variant](Enum::Variant), [`Missing`] and [anchor](#section).",
+            &links
+        ), @r###"
+        See :rust:struct:`Struct <my_crate::Struct>`, :rust:enum:`variant <my_crate::Enum>`, ``Missing`` and anchor.
+        "###);
+    }
+}
@@ -1,5 +1,7 @@
 //! Mapping of the analyzer data model to pyo3 classes
 
+use std::collections::BTreeMap;
+
 use pyo3::prelude::*;
 
 use analyzer::data_model;
@@ -12,6 +14,38 @@ pub struct Crate {
     pub name: String,
     #[pyo3(get)]
     pub version: String,
+    #[pyo3(get)]
+    pub edition: Option<String>,
+    #[pyo3(get)]
+    pub description: Option<String>,
+    #[pyo3(get)]
+    pub license: Option<String>,
+    #[pyo3(get)]
+    pub repository: Option<String>,
+    #[pyo3(get)]
+    pub homepage: Option<String>,
+    #[pyo3(get)]
+    pub documentation: Option<String>,
+    #[pyo3(get)]
+    pub readme: Option<String>,
+    #[pyo3(get)]
+    pub keywords: Vec<String>,
+    #[pyo3(get)]
+    pub categories: Vec<String>,
+    #[pyo3(get)]
+    pub authors: Vec<String>,
+    #[pyo3(get)]
+    pub rust_version: Option<String>,
+    #[pyo3(get)]
+    pub targets: Vec<Target>,
+    #[pyo3(get)]
+    pub dependencies: Vec<Dependency>,
+    #[pyo3(get)]
+    pub features: Vec<Feature>,
+    #[pyo3(get)]
+    pub config: CrateConfig,
+    #[pyo3(get)]
+    pub source: Option<CrateSource>,
 }
 
 #[pymethods]
@@ -34,6 +68,293 @@ impl From<data_model::Crate> for Crate {
         Crate {
             name: crate_.name,
             version: crate_.version,
+            edition: crate_.edition,
+            description: crate_.description,
+            license: crate_.license,
+            repository: crate_.repository,
+            homepage: crate_.homepage,
+            documentation: crate_.documentation,
+            readme: crate_.readme,
+            keywords: crate_.keywords,
+            categories: crate_.categories,
+            authors: crate_.authors,
+            rust_version: crate_.rust_version,
+            targets: crate_.targets.into_iter().map(Target::from).collect(),
+            dependencies: crate_
+                .dependencies
+                .into_iter()
+                .map(Dependency::from)
+                .collect(),
+            features: crate_.features.into_iter().map(Feature::from).collect(),
+            config: crate_.config.into(),
+            source: crate_.source.map(CrateSource::from),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of the analysis configuration of a crate
+pub struct CrateConfig {
+    #[pyo3(get)]
+    pub include: Vec<String>,
+    #[pyo3(get)]
+    pub exclude: Vec<String>,
+    #[pyo3(get)]
+    pub visibility: String,
+    #[pyo3(get)]
+    pub features: Option<Vec<String>>,
+    #[pyo3(get)]
+    pub doc_format: Option<String>,
+    #[pyo3(get)]
+    pub include_hidden: bool,
+}
+
+#[pymethods]
+impl CrateConfig {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "CrateConfig(visibility={:?}, include_hidden={:?})",
+            self.visibility, self.include_hidden
+        )
+    }
+}
+
+impl From<data_model::CrateConfig> for CrateConfig {
+    fn from(config: data_model::CrateConfig) -> Self {
+        CrateConfig {
+            include: config.include,
+            exclude: config.exclude,
+            visibility: config.visibility.as_str().to_string(),
+            features: config.features,
+            doc_format: config.doc_format.map(|f| f.as_str().to_string()),
+            include_hidden: config.include_hidden,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of the source of a packaged crate
+pub struct CrateSource {
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub path: String,
+    #[pyo3(get)]
+    pub registry: Option<String>,
+}
+
+#[pymethods]
+impl CrateSource {
+    pub fn __repr__(&self) -> String {
+        format!("CrateSource(kind={:?}, path={:?})", self.kind, self.path)
+    }
+}
+
+impl From<data_model::CrateSource> for CrateSource {
+    fn from(source: data_model::CrateSource) -> Self {
+        CrateSource {
+            kind: source.kind.as_str().to_string(),
+            path: source.path,
+            registry: source.registry,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of a crate feature
+pub struct Feature {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub docstring: String,
+    #[pyo3(get)]
+    pub default: bool,
+    #[pyo3(get)]
+    pub implicit: bool,
+    #[pyo3(get)]
+    pub features: Vec<String>,
+    #[pyo3(get)]
+    pub dependencies: Vec<String>,
+    #[pyo3(get)]
+    pub dependency_features: Vec<String>,
+    #[pyo3(get)]
+    pub items: Vec<ItemRef>,
+}
+
+#[pymethods]
+impl Feature {
+    pub fn __repr__(&self) -> String {
+        format!("Feature(name={:?}, default={:?})", self.name, self.default)
+    }
+}
+
+impl From<data_model::Feature> for Feature {
+    fn from(feature: data_model::Feature) -> Self {
+        Feature {
+            name: feature.name,
+            docstring: feature.docstring,
+            default: feature.default,
+            implicit: feature.implicit,
+            features: feature.features,
+            dependencies: feature.dependencies,
+            dependency_features: feature.dependency_features,
+            items: feature.items.into_iter().map(ItemRef::from).collect(),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+/// pyo3 representation of a reference to an analyzed item
+pub struct ItemRef {
+    #[pyo3(get)]
+    pub path: Vec<String>,
+    #[pyo3(get)]
+    pub kind: String,
+}
+
+#[pymethods]
+impl ItemRef {
+    pub fn __repr__(&self) -> String {
+        format!("ItemRef({:?}, kind={:?})", self.path_str(), self.kind)
+    }
+    #[getter]
+    pub fn path_str(&self) -> String {
+        self.path.join("::")
+    }
+}
+
+impl From<data_model::ItemRef> for ItemRef {
+    fn from(item: data_model::ItemRef) -> Self {
+        ItemRef {
+            path: item.path,
+            kind: item.kind.as_str().to_string(),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+/// pyo3 representation of the changes since the previous analysis of a crate
+pub struct Changes {
+    #[pyo3(get)]
+    pub added: Vec<ItemRef>,
+    #[pyo3(get)]
+    pub changed: Vec<ItemRef>,
+    #[pyo3(get)]
+    pub removed: Vec<ItemRef>,
+    #[pyo3(get)]
+    pub parsed_files: Vec<String>,
+}
+
+#[pymethods]
+impl Changes {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Changes(added={}, changed={}, removed={}, parsed_files={})",
+            self.added.len(),
+            self.changed.len(),
+            self.removed.len(),
+            self.parsed_files.len()
+        )
+    }
+}
+
+impl From<data_model::Changes> for Changes {
+    fn from(changes: data_model::Changes) -> Self {
+        Changes {
+            added: changes.added.into_iter().map(Into::into).collect(),
+            changed: changes.changed.into_iter().map(Into::into).collect(),
+            removed: changes.removed.into_iter().map(Into::into).collect(),
+            parsed_files: changes.parsed_files,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of a crate dependency
+pub struct Dependency {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub package: Option<String>,
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub version: Option<String>,
+    #[pyo3(get)]
+    pub path: Option<String>,
+    #[pyo3(get)]
+    pub git: Option<String>,
+    #[pyo3(get)]
+    pub features: Vec<String>,
+    #[pyo3(get)]
+    pub default_features: bool,
+    #[pyo3(get)]
+    pub optional: bool,
+    #[pyo3(get)]
+    pub target: Option<String>,
+}
+
+#[pymethods]
+impl Dependency {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Dependency(name={:?}, kind={:?}, version={:?})",
+            self.name, self.kind, self.version
+        )
+    }
+}
+
+impl From<data_model::Dependency> for Dependency {
+    fn from(dependency: data_model::Dependency) -> Self {
+        Dependency {
+            name: dependency.name,
+            package: dependency.package,
+            kind: dependency.kind.as_str().to_string(),
+            version: dependency.version,
+            path: dependency.path,
+            git: dependency.git,
+            features: dependency.features,
+            default_features: dependency.default_features,
+            optional: dependency.optional,
+            target: dependency.target,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of a crate target
+pub struct Target {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub file: String,
+    #[pyo3(get)]
+    pub module: String,
+}
+
+#[pymethods]
+impl Target {
+    pub fn __repr__(&self) -> String {
+        format!("Target(name={:?}, kind={:?})", self.name, self.kind)
+    }
+}
+
+impl From<data_model::Target> for Target {
+    fn from(target: data_model::Target) -> Self {
+        Target {
+            name: target.name,
+            kind: target.kind.as_str().to_string(),
+            file: target.file,
+            module: target.module,
         }
     }
 }
@@ -48,6 +369,16 @@ pub struct Module {
     pub path: Vec<String>,
     #[pyo3(get)]
     pub docstring: String,
+    #[pyo3(get)]
+    pub doc_format: Option<String>,
+    #[pyo3(get)]
+    pub links: Vec<DocLink>,
+    #[pyo3(get)]
+    pub sections: DocSections,
+    #[pyo3(get)]
+    pub code_blocks: Vec<CodeBlock>,
+    #[pyo3(get)]
+    pub features: Vec<String>,
 }
 
 #[pymethods]
@@ -71,6 +402,230 @@ impl From<data_model::Module> for Module {
             file: module.file,
             path: module.path,
             docstring: module.docstring,
+            doc_format: module.doc_format.map(|f| f.as_str().to_string()),
+            links: module.links.into_iter().map(Into::into).collect(),
+            sections: module.sections.into(),
+            code_blocks: module.code_blocks.into_iter().map(Into::into).collect(),
+            features: module.features,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+/// pyo3 representation of the location of an item in the source code
+pub struct Span {
+    #[pyo3(get)]
+    pub file: Option<String>,
+    #[pyo3(get)]
+    pub start_line: usize,
+    #[pyo3(get)]
+    pub start_column: usize,
+    #[pyo3(get)]
+    pub end_line: usize,
+    #[pyo3(get)]
+    pub end_column: usize,
+}
+
+#[pymethods]
+impl Span {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Span(file={:?}, start=({}, {}), end=({}, {}))",
+            self.file, self.start_line, self.start_column, self.end_line, self.end_column
+        )
+    }
+}
+
+impl From<data_model::Span> for Span {
+    fn from(span: data_model::Span) -> Self {
+        Span {
+            file: span.file,
+            start_line: span.start_line,
+            start_column: span.start_column,
+            end_line: span.end_line,
+            end_column: span.end_column,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+/// pyo3 representation of a problem found during the analysis
+pub struct Diagnostic {
+    #[pyo3(get)]
+    pub severity: String,
+    #[pyo3(get)]
+    pub code: String,
+    #[pyo3(get)]
+    pub message: String,
+    #[pyo3(get)]
+    pub file: Option<String>,
+    #[pyo3(get)]
+    pub item: Vec<String>,
+    #[pyo3(get)]
+    pub span: Option<Span>,
+}
+
+#[pymethods]
+impl Diagnostic {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Diagnostic(severity={:?}, code={:?}, message={:?})",
+            self.severity, self.code, self.message
+        )
+    }
+}
+
+impl From<data_model::Diagnostic> for Diagnostic {
+    fn from(diagnostic: data_model::Diagnostic) -> Self {
+        Diagnostic {
+            severity: diagnostic.severity.as_str().to_string(),
+            code: diagnostic.code,
+            message: diagnostic.message,
+            file: diagnostic.file,
+            item: diagnostic.item,
+            span: diagnostic.span.map(Span::from),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of a link in a docstring to another item
+pub struct DocLink {
+    #[pyo3(get)]
+    pub text: String,
+    #[pyo3(get)]
+    pub target: String,
+    #[pyo3(get)]
+    pub path: Option<Vec<String>>,
+    #[pyo3(get)]
+    pub kind: Option<String>,
+}
+
+#[pymethods]
+impl DocLink {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "DocLink(target={:?}, path={:?}, kind={:?})",
+            self.target, self.path, self.kind
+        )
+    }
+}
+
+impl From<DocLink> for data_model::DocLink {
+    fn from(link: DocLink) -> Self {
+        data_model::DocLink {
+            text: link.text,
+            target: link.target,
+            path: link.path,
+            kind: link.kind.and_then(|k| data_model::ItemKind::from_name(&k)),
+        }
+    }
+}
+
+impl From<data_model::DocLink> for DocLink {
+    fn from(link: data_model::DocLink) -> Self {
+        DocLink {
+            text: link.text,
+            target: link.target,
+            path: link.path,
+            kind: link.kind.map(|k| k.as_str().to_string()),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of a docstring split into its summary, body and sections
+pub struct DocSections {
+    #[pyo3(get)]
+    pub summary: String,
+    #[pyo3(get)]
+    pub body: String,
+    #[pyo3(get)]
+    pub sections: BTreeMap<String, String>,
+}
+
+#[pymethods]
+impl DocSections {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "DocSections(summary={:?}, sections={:?})",
+            self.summary,
+            self.sections.keys().collect::<Vec<_>>()
+        )
+    }
+}
+
+impl From<data_model::DocSections> for DocSections {
+    fn from(sections: data_model::DocSections) -> Self {
+        DocSections {
+            summary: sections.summary,
+            body: sections.body,
+            sections: sections.sections,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of a fenced code block in a docstring
+pub struct CodeBlock {
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub lang: String,
+    #[pyo3(get)]
+    pub attributes: Vec<String>,
+    #[pyo3(get)]
+    pub lines: Vec<CodeLine>,
+}
+
+#[pymethods]
+impl CodeBlock {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "CodeBlock(line={}, lang={:?}, attributes={:?})",
+            self.line, self.lang, self.attributes
+        )
+    }
+}
+
+impl From<data_model::CodeBlock> for CodeBlock {
+    fn from(block: data_model::CodeBlock) -> Self {
+        CodeBlock {
+            line: block.line,
+            lang: block.lang,
+            attributes: block.attributes,
+            lines: block.lines.into_iter().map(CodeLine::from).collect(),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of a line of a code block
+pub struct CodeLine {
+    #[pyo3(get)]
+    pub text: String,
+    #[pyo3(get)]
+    pub hidden: bool,
+}
+
+#[pymethods]
+impl CodeLine {
+    pub fn __repr__(&self) -> String {
+        format!("CodeLine(text={:?}, hidden={})", self.text, self.hidden)
+    }
+}
+
+impl From<data_model::CodeLine> for CodeLine {
+    fn from(line: data_model::CodeLine) -> Self {
+        CodeLine {
+            text: line.text,
+            hidden: line.hidden,
         }
     }
 }
@@ -82,8 +637,18 @@ pub struct Field {
     #[pyo3(get)]
     pub path: Vec<String>,
     #[pyo3(get)]
+    pub span: Span,
+    #[pyo3(get)]
     pub docstring: String,
     #[pyo3(get)]
+    pub doc_format: Option<String>,
+    #[pyo3(get)]
+    pub links: Vec<DocLink>,
+    #[pyo3(get)]
+    pub sections: DocSections,
+    #[pyo3(get)]
+    pub code_blocks: Vec<CodeBlock>,
+    #[pyo3(get)]
     pub type_: Vec<TypeSegment>,
 }
 
@@ -106,7 +671,12 @@ impl From<data_model::Field> for Field {
     fn from(field: data_model::Field) -> Self {
         Field {
             path: field.path,
+            span: field.span.into(),
             docstring: field.docstring,
+            doc_format: field.doc_format.map(|f| f.as_str().to_string()),
+            links: field.links.into_iter().map(Into::into).collect(),
+            sections: field.sections.into(),
+            code_blocks: field.code_blocks.into_iter().map(Into::into).collect(),
             type_: field.type_.into_iter().map(TypeSegment::from).collect(),
         }
     }
@@ -119,9 +689,23 @@ pub struct Struct {
     #[pyo3(get)]
     pub path: Vec<String>,
     #[pyo3(get)]
+    pub span: Span,
+    #[pyo3(get)]
     pub docstring: String,
     #[pyo3(get)]
+    pub doc_format: Option<String>,
+    #[pyo3(get)]
+    pub links: Vec<DocLink>,
+    #[pyo3(get)]
+    pub sections: DocSections,
+    #[pyo3(get)]
+    pub code_blocks: Vec<CodeBlock>,
+    #[pyo3(get)]
+    pub source: Option<String>,
+    #[pyo3(get)]
     pub fields: Vec<Field>,
+    #[pyo3(get)]
+    pub features: Vec<String>,
 }
 
 #[pymethods]
@@ -143,8 +727,15 @@ impl From<data_model::Struct> for Struct {
     fn from(module: data_model::Struct) -> Self {
         Struct {
             path: module.path,
+            span: module.span.into(),
             docstring: module.docstring,
+            doc_format: module.doc_format.map(|f| f.as_str().to_string()),
+            links: module.links.into_iter().map(Into::into).collect(),
+            sections: module.sections.into(),
+            code_blocks: module.code_blocks.into_iter().map(Into::into).collect(),
+            source: module.source,
             fields: module.fields.into_iter().map(Field::from).collect(),
+            features: module.features,
         }
     }
 }
@@ -156,9 +747,23 @@ pub struct Enum {
     #[pyo3(get)]
     pub path: Vec<String>,
     #[pyo3(get)]
+    pub span: Span,
+    #[pyo3(get)]
     pub docstring: String,
     #[pyo3(get)]
+    pub doc_format: Option<String>,
+    #[pyo3(get)]
+    pub links: Vec<DocLink>,
+    #[pyo3(get)]
+    pub sections: DocSections,
+    #[pyo3(get)]
+    pub code_blocks: Vec<CodeBlock>,
+    #[pyo3(get)]
+    pub source: Option<String>,
+    #[pyo3(get)]
     pub variants: Vec<Variant>,
+    #[pyo3(get)]
+    pub features: Vec<String>,
 }
 
 #[pymethods]
@@ -180,8 +785,15 @@ impl From<data_model::Enum> for Enum {
     fn from(module: data_model::Enum) -> Self {
         Enum {
             path: module.path,
+            span: module.span.into(),
             docstring: module.docstring,
+            doc_format: module.doc_format.map(|f| f.as_str().to_string()),
+            links: module.links.into_iter().map(Into::into).collect(),
+            sections: module.sections.into(),
+            code_blocks: module.code_blocks.into_iter().map(Into::into).collect(),
+            source: module.source,
             variants: module.variants.into_iter().map(Variant::from).collect(),
+            features: module.features,
         }
     }
 }
@@ -193,7 +805,17 @@ pub struct Variant {
     #[pyo3(get)]
     pub path: Vec<String>,
     #[pyo3(get)]
+    pub span: Span,
+    #[pyo3(get)]
     pub docstring: String,
+    #[pyo3(get)]
+    pub doc_format: Option<String>,
+    #[pyo3(get)]
+    pub links: Vec<DocLink>,
+    #[pyo3(get)]
+    pub sections: DocSections,
+    #[pyo3(get)]
+    pub code_blocks: Vec<CodeBlock>,
     // TODO discriminant
     #[pyo3(get)]
     pub fields: Vec<Field>,
@@ -218,7 +840,12 @@ impl From<data_model::Variant> for Variant {
     fn from(var: data_model::Variant) -> Self {
         Variant {
             path: var.path,
+            span: var.span.into(),
             docstring: var.docstring,
+            doc_format: var.doc_format.map(|f| f.as_str().to_string()),
+            links: var.links.into_iter().map(Into::into).collect(),
+            sections: var.sections.into(),
+            code_blocks: var.code_blocks.into_iter().map(Into::into).collect(),
             fields: var.fields.into_iter().map(Field::from).collect(),
         }
     }
@@ -233,6 +860,12 @@ pub struct TypeSegment {
     pub content: String,
     #[pyo3(get)]
     pub is_path: bool,
+    /// The fully qualified name of the analyzed item that the path resolves to
+    #[pyo3(get)]
+    pub target: Option<Vec<String>>,
+    /// The kind of the analyzed item that the path resolves to
+    #[pyo3(get)]
+    pub kind: Option<String>,
 }
 
 #[pymethods]
@@ -252,10 +885,20 @@ impl From<data_model::TypeSegment> for TypeSegment {
             data_model::TypeSegment::Path(content) => TypeSegment {
                 content,
                 is_path: true,
+                target: None,
+                kind: None,
             },
             data_model::TypeSegment::String(content) => TypeSegment {
                 content,
                 is_path: false,
+                target: None,
+                kind: None,
+            },
+            data_model::TypeSegment::Resolved { text, path, kind } => TypeSegment {
+                content: text,
+                is_path: true,
+                target: Some(path),
+                kind: Some(kind.as_str().to_string()),
             },
         }
     }
@@ -268,7 +911,27 @@ pub struct Function {
     #[pyo3(get)]
     pub path: Vec<String>,
     #[pyo3(get)]
+    pub span: Span,
+    #[pyo3(get)]
     pub docstring: String,
+    #[pyo3(get)]
+    pub doc_format: Option<String>,
+    #[pyo3(get)]
+    pub links: Vec<DocLink>,
+    #[pyo3(get)]
+    pub sections: DocSections,
+    #[pyo3(get)]
+    pub code_blocks: Vec<CodeBlock>,
+    #[pyo3(get)]
+    pub source: Option<String>,
+    #[pyo3(get)]
+    pub is_unsafe: bool,
+    #[pyo3(get)]
+    pub parameters: Vec<Parameter>,
+    #[pyo3(get)]
+    pub returns: Option<Vec<TypeSegment>>,
+    #[pyo3(get)]
+    pub features: Vec<String>,
 }
 
 #[pymethods]
@@ -290,7 +953,45 @@ impl From<data_model::Function> for Function {
     fn from(field: data_model::Function) -> Self {
         Function {
             path: field.path,
+            span: field.span.into(),
             docstring: field.docstring,
+            doc_format: field.doc_format.map(|f| f.as_str().to_string()),
+            links: field.links.into_iter().map(Into::into).collect(),
+            sections: field.sections.into(),
+            code_blocks: field.code_blocks.into_iter().map(Into::into).collect(),
+            source: field.source,
+            is_unsafe: field.is_unsafe,
+            parameters: field.parameters.into_iter().map(Parameter::from).collect(),
+            returns: field
+                .returns
+                .map(|ty| ty.into_iter().map(TypeSegment::from).collect()),
+            features: field.features,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+/// pyo3 representation of a function parameter
+pub struct Parameter {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub type_: Vec<TypeSegment>,
+}
+
+#[pymethods]
+impl Parameter {
+    pub fn __repr__(&self) -> String {
+        format!("Parameter(name={:?})", self.name)
+    }
+}
+
+impl From<data_model::Parameter> for Parameter {
+    fn from(param: data_model::Parameter) -> Self {
+        Parameter {
+            name: param.name,
+            type_: param.type_.into_iter().map(TypeSegment::from).collect(),
         }
     }
 }
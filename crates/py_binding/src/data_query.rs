@@ -105,15 +105,8 @@ pub fn load_function(cache_path: &str, full_name: &str) -> PyResult<Option<Funct
 }
 
 /// Check if a path is a child of a given parent, and return the fully qualified name of the child.
-fn is_child(path: &std::path::PathBuf, parent: &Vec<String>) -> Option<String> {
-    let name = match path.file_stem() {
-        Some(name) => name,
-        None => return None,
-    };
-    let name = match name.to_str() {
-        Some(name) => name,
-        None => return None,
-    };
+fn is_child(path: &std::path::Path, parent: &[String]) -> Option<String> {
+    let name = path.file_stem()?.to_str()?;
     let name_path = name.split("::").collect::<Vec<_>>();
     if name_path.len() != parent.len() + 1 {
         return None;
@@ -215,11 +208,7 @@ pub fn load_child_functions(cache_path: &str, parent: Vec<String>) -> PyResult<V
 }
 
 /// Check if a path is an ancestor of a given parent, and return the fully qualified name of the child.
-fn is_ancestor(
-    path: &std::path::PathBuf,
-    parent: &Vec<String>,
-    include_self: bool,
-) -> Option<String> {
+fn is_ancestor(path: &std::path::Path, parent: &[String], include_self: bool) -> Option<String> {
     let name = path.file_stem()?.to_str()?;
     let name_path = name.split("::").collect::<Vec<_>>();
     if include_self && name_path == parent.iter().map(|s| s.as_str()).collect::<Vec<_>>() {
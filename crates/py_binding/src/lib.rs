@@ -9,9 +9,26 @@
 //! We need to integrate Sphinx with Rust so that we can use the `sphinx_rust` backend to generate documentation for Rust code.
 //! ```
 
-use pyo3::{exceptions::PyIOError, prelude::*};
+use std::sync::Arc;
 
-use analyzer::analyze;
+use pyo3::{
+    create_exception,
+    exceptions::{PyIOError, PyTypeError},
+    prelude::*,
+    types::PyDict,
+};
+
+use analyzer::{
+    analyze,
+    data_model::{DocFormat, ItemKind},
+};
+
+create_exception!(
+    sphinx_rust,
+    ParseError,
+    PyIOError,
+    "Raised when a Rust source file cannot be parsed, with the location of the error"
+);
 
 pub mod data_model;
 pub mod data_query;
@@ -22,15 +39,35 @@ pub mod data_query;
 // else Python will not be able to import the module.
 fn sphinx_rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("ParseError", m.py().get_type_bound::<ParseError>())?;
     m.add_function(wrap_pyfunction!(analyze_crate, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_workspace, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_root_module, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_source, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze_package, m)?)?;
+    m.add_function(wrap_pyfunction!(markdown_to_rst, m)?)?;
     m.add_class::<data_model::Crate>()?;
+    m.add_class::<data_model::CrateConfig>()?;
+    m.add_class::<data_model::CrateSource>()?;
+    m.add_class::<data_model::Target>()?;
+    m.add_class::<data_model::Dependency>()?;
+    m.add_class::<data_model::Feature>()?;
+    m.add_class::<data_model::ItemRef>()?;
+    m.add_class::<data_model::Changes>()?;
     m.add_class::<data_model::Module>()?;
+    m.add_class::<data_model::Span>()?;
+    m.add_class::<data_model::Diagnostic>()?;
+    m.add_class::<data_model::DocLink>()?;
+    m.add_class::<data_model::DocSections>()?;
+    m.add_class::<data_model::CodeBlock>()?;
+    m.add_class::<data_model::CodeLine>()?;
     m.add_class::<data_model::Struct>()?;
     m.add_class::<data_model::Field>()?;
     m.add_class::<data_model::TypeSegment>()?;
     m.add_class::<data_model::Enum>()?;
     m.add_class::<data_model::Variant>()?;
     m.add_class::<data_model::Function>()?;
+    m.add_class::<data_model::Parameter>()?;
     m.add_class::<AnalysisResult>()?;
     m.add_function(wrap_pyfunction!(data_query::load_crate, m)?)?;
     m.add_function(wrap_pyfunction!(data_query::load_module, m)?)?;
@@ -48,9 +85,241 @@ fn sphinx_rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
 }
 
 #[pyfunction]
+#[pyo3(signature = (crate_path, cache_path, **options))]
 /// analyse a crate and cache the results to disk
-pub fn analyze_crate(crate_path: &str, cache_path: &str) -> PyResult<AnalysisResult> {
-    // check that the cache path is a directory
+pub fn analyze_crate(
+    crate_path: &str,
+    cache_path: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<AnalysisResult> {
+    let options = Options::from_kwargs(options)?;
+    run_and_cache(cache_path, &options, "Could not analyze crate", |options| {
+        Ok(vec![(analyze::analyze_crate(crate_path, options)?, None)])
+    })
+    .map(|mut outputs| outputs.remove(0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, root_module, cache_path, *, version = None, edition = None, **options))]
+/// analyse a crate without a Cargo.toml, from its root module file, and cache the results to disk
+pub fn analyze_root_module(
+    name: &str,
+    root_module: &str,
+    cache_path: &str,
+    version: Option<&str>,
+    edition: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<AnalysisResult> {
+    let options = Options::from_kwargs(options)?;
+    run_and_cache(cache_path, &options, "Could not analyze crate", |options| {
+        let result = analyze::analyze_root_module(name, root_module, version, edition, options)?;
+        Ok(vec![(result, None)])
+    })
+    .map(|mut outputs| outputs.remove(0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (name, files, cache_path, **options))]
+/// analyse a crate from source held in memory and cache the results to disk
+pub fn analyze_source(
+    name: &str,
+    files: std::collections::BTreeMap<String, String>,
+    cache_path: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<AnalysisResult> {
+    let options = Options::from_kwargs(options)?;
+    run_and_cache(cache_path, &options, "Could not analyze crate", |options| {
+        Ok(vec![(
+            analyze::analyze_source(name, &files, options)?,
+            None,
+        )])
+    })
+    .map(|mut outputs| outputs.remove(0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (package_path, cache_path, **options))]
+/// analyse a packaged crate, i.e. a ``.crate`` archive, or a vendored or registry crate directory,
+/// and cache the results to disk
+pub fn analyze_package(
+    package_path: &str,
+    cache_path: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<AnalysisResult> {
+    let options = Options::from_kwargs(options)?;
+    run_and_cache(
+        cache_path,
+        &options,
+        "Could not analyze package",
+        |options| {
+            Ok(vec![(
+                analyze::analyze_package(package_path, options)?,
+                None,
+            )])
+        },
+    )
+    .map(|mut outputs| outputs.remove(0))
+}
+
+#[pyfunction]
+#[pyo3(signature = (workspace_path, cache_path, **options))]
+/// analyse all members of a workspace and cache the results to disk
+pub fn analyze_workspace(
+    workspace_path: &str,
+    cache_path: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<AnalysisResult>> {
+    let options = Options::from_kwargs(options)?;
+    run_and_cache(
+        cache_path,
+        &options,
+        "Could not analyze workspace",
+        |options| {
+            let workspace = analyze::analyze_workspace(workspace_path, options)?;
+            Ok(workspace
+                .crates
+                .into_iter()
+                .zip(workspace.members)
+                .map(|(result, member)| (result, Some(member.path)))
+                .collect())
+        },
+    )
+}
+
+/// The keyword options of the analysis functions
+struct Options {
+    /// Whether to include function bodies in the source code snippets
+    function_bodies: bool,
+    /// The directory to write the Rust code blocks of docstrings to, as standalone test files
+    doctest_path: Option<String>,
+    /// Whether to fail if a module cannot be read or parsed
+    strict: bool,
+    /// The default format of docstrings, by crate name
+    doc_formats: std::collections::BTreeMap<String, DocFormat>,
+    /// Whether to reuse the modules parsed by previous analyses
+    incremental: bool,
+}
+
+impl Options {
+    /// Extract the options from the keyword arguments of an analysis function,
+    /// with the defaults for those that are not given
+    fn from_kwargs(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut options = Self {
+            function_bodies: true,
+            doctest_path: None,
+            strict: false,
+            doc_formats: Default::default(),
+            incremental: true,
+        };
+        for (key, value) in kwargs.into_iter().flat_map(|kwargs| kwargs.iter()) {
+            let key = key.extract::<String>()?;
+            let invalid =
+                |err: PyErr| PyTypeError::new_err(format!("invalid value for `{key}`: {err}"));
+            match key.as_str() {
+                "function_bodies" => options.function_bodies = value.extract().map_err(invalid)?,
+                "doctest_path" => options.doctest_path = value.extract().map_err(invalid)?,
+                "strict" => options.strict = value.extract().map_err(invalid)?,
+                "doc_formats" => {
+                    options.doc_formats = parse_doc_formats(value.extract().map_err(invalid)?)
+                }
+                "incremental" => options.incremental = value.extract().map_err(invalid)?,
+                _ => {
+                    return Err(PyTypeError::new_err(format!(
+                        "unexpected keyword argument `{key}`"
+                    )))
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Run an analysis with the options, then write the doctests of the analysed crates,
+/// and cache their results to disk
+///
+/// :param message: The message of the error raised if the analysis fails
+/// :param analyze: Analyse one or more crates, returning each with its path in a workspace, if any
+fn run_and_cache(
+    cache_path: &str,
+    options: &Options,
+    message: &str,
+    analyze: impl FnOnce(
+        &analyze::AnalysisOptions,
+    ) -> anyhow::Result<Vec<(analyze::crate_::AnalysisResult, Option<String>)>>,
+) -> PyResult<Vec<AnalysisResult>> {
+    let cache_path = check_cache_path(cache_path)?;
+    let cache = load_manifest(cache_path, options.incremental);
+
+    // perform the analysis
+    let analysis_options = analyze::AnalysisOptions {
+        function_bodies: options.function_bodies,
+        strict: options.strict,
+        doc_formats: options.doc_formats.clone(),
+        cache: cache.clone(),
+        ..Default::default()
+    };
+    let results = analyze(&analysis_options).map_err(|err| error_chain(message, err))?;
+
+    let mut outputs = vec![];
+    for (result, member_path) in &results {
+        if let Some(doctest_path) = &options.doctest_path {
+            // the doctests of workspace members are written to a directory per crate
+            let mut doctest_path = std::path::PathBuf::from(doctest_path);
+            if member_path.is_some() {
+                doctest_path.push(&result.crate_.name);
+            }
+            analyze::doctest::write_doctests(result, &doctest_path)
+                .map_err(|err| error_chain("Could not write doctests", err))?;
+        }
+        let mut output = cache_result(result, cache_path)?;
+        if let Some(member_path) = member_path {
+            output.path.clone_from(member_path);
+        }
+        outputs.push(output);
+    }
+    save_manifest(cache.as_deref(), cache_path)?;
+    Ok(outputs)
+}
+
+/// The name of the manifest file of incremental analyses, in the cache directory
+const MANIFEST: &str = "manifest.json";
+
+/// Load the modules parsed by previous analyses from the cache directory,
+/// if the analysis is incremental
+fn load_manifest(
+    cache_path: &std::path::Path,
+    incremental: bool,
+) -> Option<Arc<analyze::ModuleCache>> {
+    incremental.then(|| Arc::new(analyze::ModuleCache::load(&cache_path.join(MANIFEST))))
+}
+
+/// Save the modules parsed by an incremental analysis to the cache directory,
+/// once its results have been cached
+fn save_manifest(
+    cache: Option<&analyze::ModuleCache>,
+    cache_path: &std::path::Path,
+) -> PyResult<()> {
+    if let Some(cache) = cache {
+        cache
+            .save(&cache_path.join(MANIFEST))
+            .map_err(|err| error_chain("Could not save manifest", err))?;
+    }
+    Ok(())
+}
+
+/// Parse the default docstring formats of crates, ignoring unknown format names
+fn parse_doc_formats(
+    doc_formats: Option<std::collections::HashMap<String, String>>,
+) -> std::collections::BTreeMap<String, DocFormat> {
+    doc_formats
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, format)| Some((name, DocFormat::from_name(&format)?)))
+        .collect()
+}
+
+/// Check that the cache path is an existing directory
+fn check_cache_path(cache_path: &str) -> PyResult<&std::path::Path> {
     let cache_path = std::path::Path::new(cache_path);
     if !cache_path.is_dir() {
         return Err(PyIOError::new_err(format!(
@@ -58,32 +327,57 @@ pub fn analyze_crate(crate_path: &str, cache_path: &str) -> PyResult<AnalysisRes
             cache_path.to_string_lossy()
         )));
     }
+    Ok(cache_path)
+}
 
-    // perform the analysis
-    let result = match analyze::analyze_crate(crate_path) {
-        Ok(result) => result,
-        Err(err) => {
-            return Err(PyIOError::new_err(format!(
-                "Could not analyze crate: {}",
-                err.chain()
-                    .map(|err| err.to_string())
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            )))
-        }
+/// Convert an error, and its causes, to a Python IOError,
+/// or a ``ParseError`` with the location of the error, if a file could not be parsed
+fn error_chain(message: &str, err: anyhow::Error) -> PyErr {
+    let message = format!(
+        "{}: {}",
+        message,
+        err.chain()
+            .map(|err| err.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    let Some(parse_error) = err.downcast_ref::<analyze::error::ParseError>() else {
+        return PyIOError::new_err(message);
     };
+    let py_err = ParseError::new_err(message);
+    let located = Python::with_gil(|py| -> PyResult<()> {
+        let value = py_err.value_bound(py);
+        value.setattr("file", parse_error.span.file.clone())?;
+        value.setattr("line", parse_error.span.start_line)?;
+        value.setattr("column", parse_error.span.start_column)?;
+        value.setattr("excerpt", parse_error.excerpt())?;
+        Ok(())
+    });
+    match located {
+        Ok(()) => py_err,
+        Err(err) => err,
+    }
+}
 
+/// Cache the items of an analysed crate to disk
+fn cache_result(
+    result: &analyze::crate_::AnalysisResult,
+    cache_path: &std::path::Path,
+) -> PyResult<AnalysisResult> {
     let mut output = AnalysisResult::default();
 
     // now cache the results
     // note we don't write to disk, if the file already exists and has the same contents
     // this is because Sphinx uses the file's mtime in determining whether to rebuild
-    // TODO should also delete files that refer to objects that no longer exist
+    // TODO should also delete files that refer to objects that no longer exist,
+    // for analyses that are not incremental, and so do not know the removed items
     let crates_path = cache_path.join("crates");
     if !crates_path.exists() {
         std::fs::create_dir(&crates_path)?;
     }
     output.crate_ = result.crate_.name.clone();
+    output.filtered = result.filtered.iter().cloned().map(Into::into).collect();
+    output.diagnostics = result.diagnostics.iter().cloned().map(Into::into).collect();
     let crate_path = crates_path.join(format!("{}.json", result.crate_.name));
     serialize_to_file(&crate_path, &result.crate_)?;
 
@@ -123,15 +417,45 @@ pub fn analyze_crate(crate_path: &str, cache_path: &str) -> PyResult<AnalysisRes
         let func_path = funcs_path.join(format!("{}.json", func.path_str()));
         serialize_to_file(&func_path, &func)?;
     }
+
+    // delete the files of the items removed since the previous analysis
+    if let Some(changes) = &result.changes {
+        for item in &changes.removed {
+            let items_path = match item.kind {
+                ItemKind::Module => &modules_path,
+                ItemKind::Struct => &structs_path,
+                ItemKind::Enum => &enums_path,
+                ItemKind::Function => &funcs_path,
+                ItemKind::Variant | ItemKind::Field => continue,
+            };
+            let item_path = items_path.join(format!("{}.json", item.path.join("::")));
+            if item_path.exists() {
+                std::fs::remove_file(&item_path)?;
+            }
+        }
+        output.changes = Some(changes.clone().into());
+    }
     Ok(output)
 }
 
+#[pyfunction]
+#[pyo3(signature = (markdown, links = vec![]))]
+/// convert a rustdoc-flavoured Markdown docstring to reStructuredText
+pub fn markdown_to_rst(markdown: &str, links: Vec<data_model::DocLink>) -> String {
+    let links = links.into_iter().map(Into::into).collect::<Vec<_>>();
+    analyzer::markdown::markdown_to_rst(markdown, &links)
+}
+
 #[pyclass]
 #[derive(Debug, Clone, Default)]
 /// pyo3 representation of the result of an analysis
 pub struct AnalysisResult {
     #[pyo3(get)]
     pub crate_: String,
+    /// The path to the crate directory, relative to the workspace root,
+    /// or empty if a single crate was analyzed
+    #[pyo3(get)]
+    pub path: String,
     #[pyo3(get)]
     pub modules: Vec<String>,
     #[pyo3(get)]
@@ -140,6 +464,15 @@ pub struct AnalysisResult {
     pub enums: Vec<String>,
     #[pyo3(get)]
     pub functions: Vec<String>,
+    /// The modules and items excluded by the include/exclude filters
+    #[pyo3(get)]
+    pub filtered: Vec<data_model::ItemRef>,
+    /// The problems found during the analysis
+    #[pyo3(get)]
+    pub diagnostics: Vec<data_model::Diagnostic>,
+    /// The changes since the previous analysis, if the analysis was incremental
+    #[pyo3(get)]
+    pub changes: Option<data_model::Changes>,
 }
 
 #[pymethods]
@@ -168,14 +501,11 @@ where
         }
     };
     if path.exists() {
-        match std::fs::read_to_string(path) {
-            Ok(old_value) => {
-                if value == old_value {
-                    return Ok(());
-                }
+        if let Ok(old_value) = std::fs::read_to_string(path) {
+            if value == old_value {
+                return Ok(());
             }
-            Err(_) => {}
-        };
+        }
     }
     match std::fs::write(path, value) {
         Err(err) => Err(PyIOError::new_err(format!(
@@ -21,6 +21,11 @@ def main() -> None:
         action="store_true",
         help="Overwrite the output directory if it exists",
     )
+    parser.add_argument(
+        "--strict",
+        action="store_true",
+        help="Fail if a module cannot be parsed, rather than skipping it",
+    )
 
     args = parser.parse_args()
     output = Path(args.output).resolve()
@@ -30,7 +35,10 @@ def main() -> None:
         )
         sys.exit(1)
     output.mkdir(parents=True, exist_ok=True)
-    result = analyze_crate(args.crate, str(output))
+    # the output only holds the analysis, without a manifest for later analyses
+    result = analyze_crate(
+        args.crate, str(output), strict=args.strict, incremental=False
+    )
     print("Written analysis to", output)  # noqa: T201
     print(result)  # noqa: T201
 
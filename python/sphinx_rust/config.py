@@ -12,21 +12,36 @@ class RustConfig:
     """Configuration for the Rust extension."""
 
     rust_crates: list[str]
+    rust_workspaces: list[str]
+    rust_root_modules: list[dict[str, str]]
+    rust_packages: list[str]
     rust_doc_formats: dict[str, str]
     rust_viewcode: bool
+    rust_strict: bool
+    rust_incremental: bool
 
     @classmethod
     def from_app(cls, app: Sphinx) -> RustConfig:
         """Create a new RustConfig from the Sphinx application."""
         return cls(
             rust_crates=app.config.rust_crates,
+            rust_workspaces=app.config.rust_workspaces,
+            rust_root_modules=app.config.rust_root_modules,
+            rust_packages=app.config.rust_packages,
             rust_doc_formats=app.config.rust_doc_formats,
             rust_viewcode=app.config.rust_viewcode,
+            rust_strict=app.config.rust_strict,
+            rust_incremental=app.config.rust_incremental,
         )
 
     @staticmethod
     def add_configs(app: Sphinx) -> None:
         """Add the configuration values for the Rust domain."""
         app.add_config_value("rust_crates", [], "env")
+        app.add_config_value("rust_workspaces", [], "env")
+        app.add_config_value("rust_root_modules", [], "env")
+        app.add_config_value("rust_packages", [], "env")
         app.add_config_value("rust_doc_formats", {}, "env")
         app.add_config_value("rust_viewcode", True, "env")
+        app.add_config_value("rust_strict", False, "env")
+        app.add_config_value("rust_incremental", True, "env")
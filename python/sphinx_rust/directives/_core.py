@@ -9,12 +9,13 @@ from sphinx.util.docutils import LoggingReporter, SphinxDirective
 from sphinx.util.logging import getLogger
 
 from sphinx_rust.config import RustConfig
+from sphinx_rust.sphinx_rust import markdown_to_rst
 
 if TYPE_CHECKING:
     from sphinx.environment import BuildEnvironment
 
     from sphinx_rust.domain import ObjType, RustDomain
-    from sphinx_rust.sphinx_rust import TypeSegment
+    from sphinx_rust.sphinx_rust import DocLink, TypeSegment
 
 
 LOGGER = getLogger(__name__)
@@ -116,6 +117,10 @@ class DocstringItem(Protocol):
     """Fully qualified name of the item."""
     docstring: str
     """The docstring of the item."""
+    doc_format: str | None
+    """The format of the docstring, if specified."""
+    links: list[DocLink]
+    """The links in the docstring to other items."""
 
 
 def parse_docstring(
@@ -134,7 +139,14 @@ def parse_docstring(
     :param docstring: If not ``None`` then use this as the docstring, rather than the items.
     """
     config = RustConfig.from_app(env.app)
-    parser_type = config.rust_doc_formats.get(item.path[0], "restructuredtext")
+    parser_type = item.doc_format or config.rust_doc_formats.get(
+        item.path[0], "restructuredtext"
+    )
+    docstring = item.docstring if docstring is None else docstring
+    if parser_type == "rustdoc":
+        # rustdoc-flavoured Markdown is converted to reStructuredText by the backend
+        docstring = markdown_to_rst(docstring, item.links)
+        parser_type = "restructuredtext"
     try:
         parser = env.app.registry.create_source_parser(env.app, parser_type)
     except SphinxError as e:
@@ -145,7 +157,6 @@ def parse_docstring(
         )
         return []
 
-    docstring = item.docstring if docstring is None else docstring
     source_path = env.doc2path(  # TODO this actually should be the rust file path
         env.docname
     )
@@ -162,12 +173,18 @@ def parse_docstring(
 
 
 def create_object_xref(
-    docname: str, full_name: str, objtype: ObjType, *, warn_dangling: bool = False
+    docname: str,
+    full_name: str,
+    objtype: ObjType,
+    *,
+    warn_dangling: bool = False,
+    text: str | None = None,
 ) -> addnodes.pending_xref:
     """Create a cross-reference node to a rust object.
 
     :param docname: The document name.
     :param path: The fully qualified path to the object, e.g. ``crate::module::Item``.
+    :param text: The text of the reference, by default the name of the object.
     """
     options = {
         "refdoc": docname,
@@ -178,7 +195,7 @@ def create_object_xref(
         "reftarget": full_name,
     }
     ref = addnodes.pending_xref(full_name, **options)
-    name = full_name.split("::")[-1]
+    name = full_name.split("::")[-1] if text is None else text
     ref += nodes.literal(name, name)
 
     return ref
@@ -213,12 +230,25 @@ def create_source_xref(
     return ref
 
 
-def type_segs_to_nodes(segs: list[TypeSegment]) -> list[nodes.Node]:
-    """Convert a list of type segments to nodes."""
+def type_segs_to_nodes(segs: list[TypeSegment], docname: str) -> list[nodes.Node]:
+    """Convert a list of type segments to nodes.
+
+    Paths that resolve to analyzed items are converted to cross-references.
+
+    :param docname: The document name.
+    """
     nodes_: list[nodes.Node] = []
     for seg in segs:
-        if seg.is_path:
-            # TODO create cross-reference
+        if seg.target is not None and seg.kind is not None:
+            nodes_.append(
+                create_object_xref(
+                    docname,
+                    "::".join(seg.target),
+                    seg.kind,  # type: ignore[arg-type]
+                    text=seg.content,
+                )
+            )
+        elif seg.is_path:
             nodes_.append(nodes.strong("", seg.content))
         else:
             nodes_.append(nodes.Text(seg.content))
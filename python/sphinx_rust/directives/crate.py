@@ -18,6 +18,7 @@ from sphinx_rust.sphinx_rust import (
 
 from ._core import (
     RustAutoDirective,
+    create_field_list,
     create_object_xref,
     create_source_xref,
     create_summary_table,
@@ -26,7 +27,15 @@ from ._core import (
 
 if TYPE_CHECKING:
     from sphinx_rust.domain import ObjType
-    from sphinx_rust.sphinx_rust import Enum, Function, Module, Struct
+    from sphinx_rust.sphinx_rust import (
+        Crate,
+        Dependency,
+        Enum,
+        Feature,
+        Function,
+        Module,
+        Struct,
+    )
 
 LOGGER = getLogger(__name__)
 
@@ -63,8 +72,14 @@ class RustCrateAutoDirective(RustAutoDirective):
 
         root = nodes.Element()
 
-        # root += create_field_list(([nodes.Text("Version")], [nodes.Text(crate.version)]))
-        root += nodes.paragraph("", f"Version: {crate.version}")
+        if crate.description:
+            root += nodes.paragraph("", crate.description)
+        root += create_field_list(
+            [
+                ([nodes.Text(name)], [nodes.paragraph("", "", *value)])
+                for name, value in crate_metadata(crate)
+            ]
+        )
 
         desc = addnodes.desc()
         root += desc
@@ -126,4 +141,147 @@ class RustCrateAutoDirective(RustAutoDirective):
                 ]
                 section += create_summary_table(rows)  # type: ignore[arg-type]
 
+        # the other targets, e.g. binaries, are documented as their own module trees
+        if targets := [t for t in crate.targets if t.module != crate.name]:
+            section = self.create_section("Targets")
+            root += section
+            section += create_summary_table(
+                [
+                    (
+                        [
+                            nodes.paragraph(
+                                "",
+                                "",
+                                create_object_xref(
+                                    self.env.docname,
+                                    target.module,
+                                    "module",
+                                    text=target.name,
+                                ),
+                            )
+                        ],
+                        [nodes.paragraph("", target.kind)],
+                    )
+                    for target in targets
+                ]
+            )
+
+        if crate.features:
+            section = self.create_section("Features")
+            root += section
+            section += create_summary_table(
+                [
+                    (
+                        [nodes.paragraph("", "", nodes.literal("", feature.name))],
+                        [
+                            nodes.paragraph("", feature_summary(feature)),
+                            *(
+                                [
+                                    nodes.paragraph(
+                                        "",
+                                        "Gates: ",
+                                        *joined_xrefs(
+                                            [
+                                                create_object_xref(
+                                                    self.env.docname,
+                                                    item.path_str,
+                                                    item.kind,
+                                                )
+                                                for item in feature.items
+                                            ]
+                                        ),
+                                    )
+                                ]
+                                if feature.items
+                                else []
+                            ),
+                        ],
+                    )
+                    for feature in crate.features
+                ]
+            )
+
+        if crate.dependencies:
+            section = self.create_section("Dependencies")
+            root += section
+            section += create_summary_table(
+                [
+                    (
+                        [nodes.paragraph("", "", nodes.literal("", dep.name))],
+                        [nodes.paragraph("", dependency_summary(dep))],
+                    )
+                    for dep in crate.dependencies
+                ]
+            )
+
         return root.children
+
+
+def crate_metadata(crate: Crate) -> list[tuple[str, list[nodes.Node]]]:
+    """Create the (non-empty) metadata fields of a crate."""
+    fields: list[tuple[str, list[nodes.Node]]] = [
+        ("Version", [nodes.Text(crate.version)])
+    ]
+    for name, value in [
+        ("Edition", crate.edition),
+        ("Rust version", crate.rust_version),
+        ("License", crate.license),
+        ("Authors", ", ".join(crate.authors)),
+        ("Keywords", ", ".join(crate.keywords)),
+        ("Categories", ", ".join(crate.categories)),
+    ]:
+        if value:
+            fields.append((name, [nodes.Text(value)]))
+    for name, url in [
+        ("Repository", crate.repository),
+        ("Homepage", crate.homepage),
+        ("Documentation", crate.documentation),
+    ]:
+        if url:
+            fields.append((name, [nodes.reference("", url, refuri=url)]))
+    return fields
+
+
+def feature_summary(feature: Feature) -> str:
+    """Summarise the documentation and effects of a feature."""
+    summary = feature.docstring.splitlines()[0] if feature.docstring else ""
+    details = ["default"] if feature.default else []
+    if feature.features:
+        details.append(f"enables {', '.join(feature.features)}")
+    if deps := feature.dependencies + feature.dependency_features:
+        details.append(f"dependencies: {', '.join(deps)}")
+    if not details:
+        return summary or "-"
+    return f"{summary} ({'; '.join(details)})" if summary else "; ".join(details)
+
+
+def joined_xrefs(xrefs: list[nodes.Node]) -> list[nodes.Node]:
+    """Join cross-references with commas."""
+    joined: list[nodes.Node] = []
+    for i, xref in enumerate(xrefs):
+        if i:
+            joined.append(nodes.Text(", "))
+        joined.append(xref)
+    return joined
+
+
+def dependency_summary(dep: Dependency) -> str:
+    """Summarise the source and options of a dependency."""
+    if dep.path is not None:
+        source = f"path {dep.path}"
+    elif dep.git is not None:
+        source = f"git {dep.git}"
+    else:
+        source = dep.version or "*"
+    details = [] if dep.kind == "normal" else [f"{dep.kind} dependency"]
+    if dep.package:
+        details.append(f"package {dep.package}")
+    if dep.target:
+        details.append(f"on {dep.target}")
+    if dep.optional:
+        details.append("optional")
+    if not dep.default_features:
+        details.append("no default features")
+    if dep.features:
+        details.append(f"features: {', '.join(dep.features)}")
+    return f"{source} ({'; '.join(details)})" if details else source
@@ -58,7 +58,7 @@ class RustStructAutoDirective(RustAutoDirective):
                 addnodes.desc_signature_line(
                     "",
                     f"    pub {field.name}: ",
-                    *type_segs_to_nodes(field.type_),
+                    *type_segs_to_nodes(field.type_, self.env.docname),
                     nodes.Text(","),
                 )
                 for field in struct.fields
@@ -90,7 +90,7 @@ class RustStructAutoDirective(RustAutoDirective):
                     (
                         [nodes.Text(field.name or str(i))],
                         [
-                            nodes.paragraph("", "", *type_segs_to_nodes(field.type_)),
+                            nodes.paragraph("", "", *type_segs_to_nodes(field.type_, self.env.docname)),
                             *parse_docstring(self.env, self.doc, field),
                         ],
                     )
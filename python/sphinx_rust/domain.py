@@ -18,7 +18,15 @@ from sphinx_rust.directives.enum import RustEnumAutoDirective
 from sphinx_rust.directives.function import RustFunctionAutoDirective
 from sphinx_rust.directives.module import RustModuleAutoDirective
 from sphinx_rust.directives.struct import RustStructAutoDirective
-from sphinx_rust.sphinx_rust import analyze_crate, load_descendant_modules
+from sphinx_rust.sphinx_rust import (
+    ParseError,
+    analyze_crate,
+    analyze_package,
+    analyze_root_module,
+    analyze_workspace,
+    load_crate,
+    load_descendant_modules,
+)
 
 if TYPE_CHECKING:
     from docutils.nodes import Element
@@ -99,15 +107,89 @@ class RustDomain(Domain):
             # analyze the crate
             LOGGER.info(f"[rust] Analyzing crate: {path.resolve()!s}")
             try:
-                result = analyze_crate(str(path), str(cache))
+                result = analyze_crate(
+                    str(path),
+                    str(cache),
+                    strict=config.rust_strict,
+                    doc_formats=config.rust_doc_formats,
+                    incremental=config.rust_incremental,
+                )
             except OSError as e:
-                LOGGER.warning(
-                    f"Error analyzing crate: {e!s}", type="rust", subtype="analyze"
+                log_analysis_error("crate", e, path)
+                continue
+            report_changes(result)
+            create_pages(srcdir, result)
+            report_diagnostics(path, result)
+            if config.rust_viewcode:
+                create_code_pages(result.crate_, path, srcdir, cache)
+        for workspace in config.rust_workspaces:
+            path = Path(str(app.srcdir)) / str(workspace)
+            # analyze all members of the workspace
+            LOGGER.info(f"[rust] Analyzing workspace: {path.resolve()!s}")
+            try:
+                results = analyze_workspace(
+                    str(path),
+                    str(cache),
+                    strict=config.rust_strict,
+                    doc_formats=config.rust_doc_formats,
+                    incremental=config.rust_incremental,
                 )
-                return
+            except OSError as e:
+                log_analysis_error("workspace", e, path)
+                continue
+            for result in results:
+                report_changes(result)
+                create_pages(srcdir, result)
+                report_diagnostics(path / result.path, result)
+                if config.rust_viewcode:
+                    create_code_pages(
+                        result.crate_, path / result.path, srcdir, cache
+                    )
+        for root_module in config.rust_root_modules:
+            path = Path(str(app.srcdir)) / str(root_module["path"])
+            # analyze a crate without a Cargo.toml
+            LOGGER.info(f"[rust] Analyzing root module: {path.resolve()!s}")
+            try:
+                result = analyze_root_module(
+                    root_module["name"],
+                    str(path),
+                    str(cache),
+                    version=root_module.get("version"),
+                    edition=root_module.get("edition"),
+                    strict=config.rust_strict,
+                    doc_formats=config.rust_doc_formats,
+                    incremental=config.rust_incremental,
+                )
+            except (OSError, KeyError) as e:
+                log_analysis_error("root module", e, path.parent)
+                continue
+            report_changes(result)
             create_pages(srcdir, result)
+            report_diagnostics(path.parent, result)
             if config.rust_viewcode:
-                create_code_pages(result.crate_, srcdir, cache)
+                # module file paths are relative to the directory of the root module
+                create_code_pages(result.crate_, path.parent, srcdir, cache)
+        for package in config.rust_packages:
+            path = Path(str(app.srcdir)) / str(package)
+            # analyze a packaged crate, e.g. a third-party dependency
+            LOGGER.info(f"[rust] Analyzing package: {path.resolve()!s}")
+            try:
+                result = analyze_package(
+                    str(path),
+                    str(cache),
+                    strict=config.rust_strict,
+                    doc_formats=config.rust_doc_formats,
+                    incremental=config.rust_incremental,
+                )
+            except OSError as e:
+                log_analysis_error("package", e, path)
+                continue
+            report_changes(result)
+            create_pages(srcdir, result)
+            report_diagnostics(path, result)
+            # the files of an archive are not extracted to disk
+            if config.rust_viewcode and path.is_dir():
+                create_code_pages(result.crate_, path, srcdir, cache)
 
     @property
     def objects(self) -> dict[str, ObjectEntry]:
@@ -198,6 +280,10 @@ def create_pages(srcdir: Path, result: AnalysisResult) -> None:
         shutil.rmtree(root)
     root.mkdir(parents=True, exist_ok=True)
     root.joinpath(".gitignore").write_text("*\n")
+    if result.filtered:
+        LOGGER.info(
+            f"[rust] Filtered out {len(result.filtered)} items of crate: {result.crate_}"
+        )
 
     # create the sub-indexes
     indexes = []
@@ -224,6 +310,56 @@ def create_pages(srcdir: Path, result: AnalysisResult) -> None:
     root.joinpath("index.rst").write_text(index_content)
 
 
+def log_analysis_error(what: str, error: Exception, path: Path | None = None) -> None:
+    """Log an error that stopped the analysis, at its location if it is a parse error.
+
+    :param path: The crate or workspace directory, which the file of a parse error is relative to.
+    """
+    location = None
+    if isinstance(error, ParseError) and path is not None and error.file is not None:
+        location = f"{path / error.file}:{error.line}"
+    LOGGER.warning(
+        f"Error analyzing {what}: {error!s}",
+        type="rust",
+        subtype="analyze",
+        location=location,
+    )
+
+
+def report_changes(result: AnalysisResult) -> None:
+    """Log the changes since the previous analysis of a crate, if it was incremental."""
+    if result.changes is None:
+        return
+    changes = result.changes
+    LOGGER.info(
+        f"[rust] {result.crate_}: {len(changes.parsed_files)} files parsed, "
+        f"{len(changes.added)} items added, {len(changes.changed)} changed, "
+        f"{len(changes.removed)} removed"
+    )
+
+
+def report_diagnostics(path: Path, result: AnalysisResult) -> None:
+    """Log the diagnostics of an analyzed crate, at their location in the source code.
+
+    :param path: The crate directory, which the file paths of the diagnostics are relative to.
+    """
+    for diagnostic in result.diagnostics:
+        location = None
+        if diagnostic.file is not None:
+            location = str(path / diagnostic.file)
+            if diagnostic.span is not None:
+                location += f":{diagnostic.span.start_line}"
+        log = {"error": LOGGER.error, "info": LOGGER.info}.get(
+            diagnostic.severity, LOGGER.warning
+        )
+        log(
+            f"[rust] {diagnostic.message}",
+            type="rust",
+            subtype=diagnostic.code,
+            location=location,
+        )
+
+
 def create_object_pages(folder: Path, otype: str, names: list[str]) -> None:
     """Create the pages for the objects of a certain type."""
     ofolder = folder.joinpath(otype + "s")
@@ -238,17 +374,23 @@ def create_object_pages(folder: Path, otype: str, names: list[str]) -> None:
     ofolder.joinpath("index.rst").write_text(index_content)
 
 
-def create_code_pages(crate_name: str, srcdir: Path, cache: Path) -> None:
+def create_code_pages(
+    crate_name: str, crate_path: Path, srcdir: Path, cache: Path
+) -> None:
+    crate = load_crate(str(cache), crate_name)
+    roots = [t.module for t in crate.targets] if crate else [crate_name]
     if modules := [
         (m.path_str, m.file)
-        for m in load_descendant_modules(str(cache), [crate_name], True)
+        for root in roots
+        for m in load_descendant_modules(str(cache), [root], True)
         if m.file
     ]:
         code_folder = srcdir.joinpath("api", "crates", crate_name, "code")
         code_folder.mkdir(exist_ok=True, parents=True)
         for full_name, file_path in modules:
             # TODO catch exceptions here, if a relative path cannot be created
-            rel_path = os.path.relpath(Path(file_path), code_folder)
+            # module file paths are stored relative to the crate root
+            rel_path = os.path.relpath(crate_path.joinpath(file_path), code_folder)
             # note, this is available only in Python 3.12+
             # rel_path = Path(file_path).relative_to(code_folder, walk_up=True)
             # TODO only write the file if it doesn't exist or is different
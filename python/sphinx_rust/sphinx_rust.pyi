@@ -1,15 +1,198 @@
 from __future__ import annotations
 
+from typing import Literal
+
 __version__: str
 
-def analyze_crate(crate_path: str, cache_path: str) -> AnalysisResult:
+class ParseError(OSError):
+    """Raised when a Rust source file cannot be parsed, in strict mode.
+
+    The message includes an excerpt of the source code, with a caret under the error.
+    """
+
+    file: str | None
+    """The path to the file, relative to the crate root."""
+    line: int
+    """The line of the error (1-based)."""
+    column: int
+    """The column of the error (0-based)."""
+    excerpt: str
+    """The location of the error, with an excerpt of the source code."""
+
+def analyze_crate(
+    crate_path: str,
+    cache_path: str,
+    *,
+    function_bodies: bool = True,
+    doctest_path: str | None = None,
+    strict: bool = False,
+    doc_formats: dict[str, str] | None = None,
+    incremental: bool = True,
+) -> AnalysisResult:
     """Analyse a crate and cache the results to disk.
 
     :param crate_path: The path to the crate to analyse.
     :param cache_path: The path to the cache directory (must exist).
+    :param function_bodies: Whether to include function bodies in the source code snippets.
+    :param doctest_path: If given, the directory to write the Rust code blocks of Markdown and rustdoc docstrings to,
+        as standalone test files, replacing those written for the crate by a previous analysis.
+    :param strict: Whether to fail if a module cannot be read or parsed,
+        rather than adding an error diagnostic and analyzing the rest of the crate.
+    :param doc_formats: The default format of docstrings, by crate name, for crates that do not configure one,
+        e.g. ``{"my_crate": "markdown"}``; docstrings are otherwise considered to be reStructuredText,
+        and their links are not resolved.
+    :param incremental: Whether to reuse the modules parsed by previous analyses,
+        for the files that have not changed since, which are recorded in a manifest
+        in the cache directory.
+    :raises ParseError: If a module cannot be parsed, in strict mode.
+    :raises IOError: If the analysis fails.
+    """
+
+def analyze_workspace(
+    workspace_path: str,
+    cache_path: str,
+    *,
+    function_bodies: bool = True,
+    doctest_path: str | None = None,
+    strict: bool = False,
+    doc_formats: dict[str, str] | None = None,
+    incremental: bool = True,
+) -> list[AnalysisResult]:
+    """Analyse all members of a workspace and cache the results to disk.
+
+    Links and type paths are resolved across members that depend on each other.
+
+    :param workspace_path: The path to the workspace, whose ``Cargo.toml`` has a ``[workspace]`` section.
+    :param cache_path: The path to the cache directory (must exist).
+    :param function_bodies: Whether to include function bodies in the source code snippets.
+    :param doctest_path: If given, the directory to write the Rust code blocks of Markdown and rustdoc docstrings to,
+        as standalone test files, replacing those written for the crate by a previous analysis.
+    :param strict: Whether to fail if a module cannot be read or parsed,
+        rather than adding an error diagnostic and analyzing the rest of the crate.
+    :param doc_formats: The default format of docstrings, by crate name, for crates that do not configure one,
+        e.g. ``{"my_crate": "markdown"}``; docstrings are otherwise considered to be reStructuredText,
+        and their links are not resolved.
+    :param incremental: Whether to reuse the modules parsed by previous analyses,
+        for the files that have not changed since, which are recorded in a manifest
+        in the cache directory.
+    :raises ParseError: If a module cannot be parsed, in strict mode.
+    :raises IOError: If the analysis fails.
+    """
+
+def analyze_root_module(
+    name: str,
+    root_module: str,
+    cache_path: str,
+    *,
+    version: str | None = None,
+    edition: str | None = None,
+    function_bodies: bool = True,
+    doctest_path: str | None = None,
+    strict: bool = False,
+    doc_formats: dict[str, str] | None = None,
+    incremental: bool = True,
+) -> AnalysisResult:
+    """Analyse a crate without a ``Cargo.toml``, from its root module file,
+    and cache the results to disk.
+
+    Paths in the analysis are relative to the directory containing the root module file.
+
+    :param name: The name of the crate.
+    :param root_module: The path to the root module file, e.g. ``src/lib.rs``.
+    :param cache_path: The path to the cache directory (must exist).
+    :param version: The version of the crate, or ``0.0.0`` if not given.
+    :param edition: The Rust edition of the crate.
+    :param function_bodies: Whether to include function bodies in the source code snippets.
+    :param doctest_path: If given, the directory to write the Rust code blocks of Markdown and rustdoc docstrings to,
+        as standalone test files, replacing those written for the crate by a previous analysis.
+    :param strict: Whether to fail if a module cannot be read or parsed,
+        rather than adding an error diagnostic and analyzing the rest of the crate.
+    :param doc_formats: The default format of docstrings, by crate name, for crates that do not configure one,
+        e.g. ``{"my_crate": "markdown"}``; docstrings are otherwise considered to be reStructuredText,
+        and their links are not resolved.
+    :param incremental: Whether to reuse the modules parsed by previous analyses,
+        for the files that have not changed since, which are recorded in a manifest
+        in the cache directory.
+    :raises ParseError: If a module cannot be parsed, in strict mode.
+    :raises IOError: If the analysis fails.
+    """
+
+def analyze_source(
+    name: str,
+    files: dict[str, str],
+    cache_path: str,
+    *,
+    function_bodies: bool = True,
+    doctest_path: str | None = None,
+    strict: bool = False,
+    doc_formats: dict[str, str] | None = None,
+    incremental: bool = True,
+) -> AnalysisResult:
+    """Analyse a crate from source held in memory and cache the results to disk.
+
+    The root module is the first of ``src/lib.rs``, ``lib.rs``, ``src/main.rs`` or ``main.rs``
+    that is given, and paths in the analysis are relative to the root of the files.
+
+    :param name: The name of the crate.
+    :param files: The contents of the source files, by their path, e.g. ``src/lib.rs``.
+    :param cache_path: The path to the cache directory (must exist).
+    :param function_bodies: Whether to include function bodies in the source code snippets.
+    :param doctest_path: If given, the directory to write the Rust code blocks of Markdown and rustdoc docstrings to,
+        as standalone test files, replacing those written for the crate by a previous analysis.
+    :param strict: Whether to fail if a module cannot be read or parsed,
+        rather than adding an error diagnostic and analyzing the rest of the crate.
+    :param doc_formats: The default format of docstrings, by crate name, for crates that do not configure one,
+        e.g. ``{"my_crate": "markdown"}``; docstrings are otherwise considered to be reStructuredText,
+        and their links are not resolved.
+    :param incremental: Whether to reuse the modules parsed by previous analyses,
+        for the files that have not changed since, which are recorded in a manifest
+        in the cache directory.
+    :raises ParseError: If a module cannot be parsed, in strict mode.
     :raises IOError: If the analysis fails.
     """
 
+def analyze_package(
+    package_path: str,
+    cache_path: str,
+    *,
+    function_bodies: bool = True,
+    doctest_path: str | None = None,
+    strict: bool = False,
+    doc_formats: dict[str, str] | None = None,
+    incremental: bool = True,
+) -> AnalysisResult:
+    """Analyse a packaged crate and cache the results to disk.
+
+    The package can be a ``.crate`` archive, which is read without extracting it,
+    a crate directory in the output of ``cargo vendor``,
+    or an extracted crate in the cargo registry, e.g. ``~/.cargo/registry/src/<registry>/<crate>``.
+
+    :param package_path: The path to the archive or crate directory.
+    :param cache_path: The path to the cache directory (must exist).
+    :param function_bodies: Whether to include function bodies in the source code snippets.
+    :param doctest_path: If given, the directory to write the Rust code blocks of Markdown and rustdoc docstrings to,
+        as standalone test files, replacing those written for the crate by a previous analysis.
+    :param strict: Whether to fail if a module cannot be read or parsed,
+        rather than adding an error diagnostic and analyzing the rest of the crate.
+    :param doc_formats: The default format of docstrings, by crate name, for crates that do not configure one,
+        e.g. ``{"my_crate": "markdown"}``; docstrings are otherwise considered to be reStructuredText,
+        and their links are not resolved.
+    :param incremental: Whether to reuse the modules parsed by previous analyses,
+        for the files that have not changed since, which are recorded in a manifest
+        in the cache directory.
+    :raises ParseError: If a module cannot be parsed, in strict mode.
+    :raises IOError: If the analysis fails.
+    """
+
+def markdown_to_rst(markdown: str, links: list[DocLink] = ...) -> str:
+    """Convert a rustdoc-flavoured Markdown docstring to reStructuredText.
+
+    Headings are converted to rubrics, and resolved links to ``:rust:`` roles.
+
+    :param markdown: The Markdown text.
+    :param links: The links of the docstring, with their resolved targets.
+    """
+
 def load_crate(cache_path: str, name: str, /) -> Crate | None:
     """Load a crate from the cache, it it exists.
 
@@ -113,10 +296,48 @@ class AnalysisResult:
     """Representation of the result of an analysis."""
 
     crate_: str
+    path: str
+    """The path to the crate directory, relative to the workspace root,
+    or empty if a single crate was analyzed."""
     modules: list[str]
     structs: list[str]
     enums: list[str]
     functions: list[str]
+    filtered: list[ItemRef]
+    """The modules and items excluded by the include/exclude filters;
+    the contents of excluded modules are not analyzed."""
+    diagnostics: list[Diagnostic]
+    """The problems found during the analysis, which did not prevent it from completing."""
+    changes: Changes | None
+    """The changes since the previous analysis of the crate, if the analysis was incremental."""
+
+class Changes:
+    """Representation of the changes since the previous analysis of a crate."""
+
+    added: list[ItemRef]
+    """The items that were not in the previous analysis."""
+    changed: list[ItemRef]
+    """The items that differ from the previous analysis, e.g. in their docstring."""
+    removed: list[ItemRef]
+    """The items of the previous analysis that no longer exist."""
+    parsed_files: list[str]
+    """The module files that were parsed again, relative to the crate root."""
+
+class Diagnostic:
+    """Representation of a problem found during the analysis."""
+
+    severity: Literal["info", "warning", "error"]
+    """How serious the problem is."""
+    code: str
+    """An identifier for the type of problem, e.g. ``unresolved-link``."""
+    message: str
+    """A description of the problem."""
+    file: str | None
+    """The path to the file that the problem is in, relative to the crate root."""
+    item: list[str]
+    """The fully qualified name of the item that the problem relates to."""
+    span: Span | None
+    """The location of the problem in the source code."""
 
 class Crate:
     """Representation of a crate."""
@@ -125,12 +346,136 @@ class Crate:
     """The name of the crate."""
     version: str
     """The version of the crate."""
+    edition: str | None
+    """The Rust edition of the crate, e.g. ``2021``."""
+    description: str | None
+    """The description of the crate."""
+    license: str | None
+    """The license of the crate, as an SPDX expression."""
+    repository: str | None
+    """The URL of the crate's source repository."""
+    homepage: str | None
+    """The URL of the crate's homepage."""
+    documentation: str | None
+    """The URL of the crate's documentation."""
+    readme: str | None
+    """The path to the readme file, relative to the crate root."""
+    keywords: list[str]
+    """The keywords of the crate."""
+    categories: list[str]
+    """The crates.io categories of the crate."""
+    authors: list[str]
+    """The authors of the crate."""
+    rust_version: str | None
+    """The minimum supported Rust version."""
+    targets: list[Target]
+    """The targets of the crate, starting with the library, if there is one."""
+    dependencies: list[Dependency]
+    """The dependencies of the crate, of all kinds."""
+    features: list[Feature]
+    """The features of the crate, excluding ``default``."""
+    config: CrateConfig
+    """The analysis configuration, from ``[package.metadata.sphinx-rust]``."""
+    source: CrateSource | None
+    """Where the crate was read from, if it is a packaged crate."""
+
+class CrateConfig:
+    """Representation of the analysis configuration of a crate."""
+
+    include: list[str]
+    """Glob patterns of item paths to include; if empty, all items are included."""
+    exclude: list[str]
+    """Glob patterns of item paths to exclude, which take precedence over ``include``."""
+    visibility: Literal["public", "crate", "private"]
+    """The minimum visibility of items to include."""
+    features: list[str] | None
+    """The features to consider enabled, or all features if not set."""
+    doc_format: str | None
+    """The default format of docstrings in the crate."""
+    include_hidden: bool
+    """Whether to include items marked with ``#[doc(hidden)]``."""
+
+class CrateSource:
+    """Representation of the source of a packaged crate."""
+
+    kind: Literal["archive", "vendor", "registry"]
+    """The kind of source."""
+    path: str
+    """The path to the archive or directory the crate was read from."""
+    registry: str | None
+    """The name of the registry directory, for a crate in ``~/.cargo/registry/src``."""
+
+class Feature:
+    """Representation of a crate feature."""
+
+    name: str
+    """The name of the feature."""
+    docstring: str
+    """The documentation of the feature, from the ``## `` comment lines above it."""
+    default: bool
+    """Whether the feature is enabled by default, directly or through other features."""
+    implicit: bool
+    """Whether the feature is implied by an optional dependency, rather than declared."""
+    features: list[str]
+    """The other features of the crate that the feature enables."""
+    dependencies: list[str]
+    """The optional dependencies that the feature enables."""
+    dependency_features: list[str]
+    """The features of dependencies that the feature enables, e.g. ``serde/derive``."""
+    items: list[ItemRef]
+    """The items that are gated on the feature."""
+
+class ItemRef:
+    """Representation of a reference to an analyzed item."""
+
+    path: list[str]
+    """The fully qualified path"""
+    path_str: str
+    """The fully qualified name as a string, e.g. ``a::b::c``"""
+    kind: Literal["module", "struct", "enum", "function"]
+    """The kind of the item."""
+
+class Dependency:
+    """Representation of a crate dependency."""
+
+    name: str
+    """The name the dependency is referred to by in ``Cargo.toml``."""
+    package: str | None
+    """The name of the package, if the dependency is renamed."""
+    kind: Literal["normal", "dev", "build"]
+    """The section the dependency is declared in."""
+    version: str | None
+    """The version requirement."""
+    path: str | None
+    """The path to a local dependency, relative to the crate root."""
+    git: str | None
+    """The URL of a git dependency."""
+    features: list[str]
+    """The features enabled for the dependency."""
+    default_features: bool
+    """Whether the default features of the dependency are enabled."""
+    optional: bool
+    """Whether the dependency is optional, i.e. enabled by a feature."""
+    target: str | None
+    """The platform the dependency is specific to, e.g. ``cfg(windows)``."""
+
+class Target:
+    """Representation of a crate target."""
+
+    name: str
+    """The name of the target, as given to cargo."""
+    kind: Literal["lib", "bin", "example", "test", "bench"]
+    """The kind of the target."""
+    file: str
+    """The path to the root module file, relative to the crate root."""
+    module: str
+    """The name of the root module of the target."""
 
 class Module:
     """Representation of a module."""
 
     file: str | None
-    """The absolute path to the file containing the module."""
+    """The path to the file containing the module, relative to the crate root."""
     name: str
     """The name of the module."""
     path: list[str]
@@ -138,6 +483,72 @@ class Module:
     path_str: str
     """The fully qualified name as a string, e.g. ``a::b::c``"""
     docstring: str
+    doc_format: str | None
+    """The format of the docstring, if specified, e.g. ``restructuredtext`` or ``markdown``."""
+    links: list[DocLink]
+    """The links in the docstring to other items."""
+    sections: DocSections
+    """The docstring, split into its summary, body and sections."""
+    code_blocks: list[CodeBlock]
+    """The fenced code blocks in the docstring, e.g. doctests."""
+    features: list[str]
+    """The features the module is gated on, with ``#[cfg(feature = "...")]``."""
+
+class Span:
+    """Representation of the location of an item in the source code."""
+
+    file: str | None
+    """The path to the file, relative to the crate root."""
+    start_line: int
+    """The line on which the item starts (1-based)."""
+    start_column: int
+    """The column at which the item starts (0-based)."""
+    end_line: int
+    """The line on which the item ends (1-based)."""
+    end_column: int
+    """The column at which the item ends (0-based)."""
+
+class DocLink:
+    """Representation of a link in a docstring to another item."""
+
+    text: str
+    """The text of the link."""
+    target: str
+    """The target of the link, as written in the docstring."""
+    path: list[str] | None
+    """The fully qualified name of the item the link resolves to, if resolved."""
+    kind: str | None
+    """The kind of item the link resolves to, e.g. ``struct``, if resolved."""
+
+class DocSections:
+    """Representation of a docstring split into its summary, body and sections."""
+
+    summary: str
+    """The first paragraph of the docstring."""
+    body: str
+    """The rest of the docstring, before the first section."""
+    sections: dict[str, str]
+    """The content of each ``# Heading`` section, by its heading, e.g. ``Examples``."""
+
+class CodeBlock:
+    """Representation of a fenced code block in a docstring, e.g. a doctest."""
+
+    line: int
+    """The line of the opening fence, relative to the start of the docstring (1-based)."""
+    lang: str
+    """The language of the block, which is ``rust`` if it only has rustdoc attributes."""
+    attributes: list[str]
+    """The rustdoc attributes of the block, e.g. ``ignore``, ``no_run`` or ``should_panic``."""
+    lines: list[CodeLine]
+    """The lines of the block."""
+
+class CodeLine:
+    """Representation of a line of a code block."""
+
+    text: str
+    """The text of the line, with any ``# `` prefix of hidden lines removed."""
+    hidden: bool
+    """Whether the line is hidden in the documentation, but still part of the doctest."""
 
 class Struct:
     """Representation of a struct."""
@@ -148,8 +559,22 @@ class Struct:
     """The fully qualified path"""
     path_str: str
     """The fully qualified name as a string, e.g. ``a::b::c``"""
+    span: Span
+    """The location of the struct in the source code."""
     docstring: str
+    doc_format: str | None
+    """The format of the docstring, if specified, e.g. ``restructuredtext`` or ``markdown``."""
+    links: list[DocLink]
+    """The links in the docstring to other items."""
+    sections: DocSections
+    """The docstring, split into its summary, body and sections."""
+    code_blocks: list[CodeBlock]
+    """The fenced code blocks in the docstring, e.g. doctests."""
+    source: str | None
+    """The source code of the struct, as written in the module file."""
     fields: list[Field]
+    features: list[str]
+    """The features the struct is gated on, with ``#[cfg(feature = "...")]``."""
 
 class Enum:
     """Representation of an enum."""
@@ -160,8 +585,22 @@ class Enum:
     """The fully qualified path"""
     path_str: str
     """The fully qualified name as a string, e.g. ``a::b::c``"""
+    span: Span
+    """The location of the enum in the source code."""
     docstring: str
+    doc_format: str | None
+    """The format of the docstring, if specified, e.g. ``restructuredtext`` or ``markdown``."""
+    links: list[DocLink]
+    """The links in the docstring to other items."""
+    sections: DocSections
+    """The docstring, split into its summary, body and sections."""
+    code_blocks: list[CodeBlock]
+    """The fenced code blocks in the docstring, e.g. doctests."""
+    source: str | None
+    """The source code of the enum, as written in the module file."""
     variants: list[Variant]
+    features: list[str]
+    """The features the enum is gated on, with ``#[cfg(feature = "...")]``."""
 
 class Variant:
     """Representation of an enum variant."""
@@ -172,7 +611,17 @@ class Variant:
     """The fully qualified path"""
     path_str: str
     """The fully qualified name as a string, e.g. ``a::b::c``"""
+    span: Span
+    """The location of the variant in the source code."""
     docstring: str
+    doc_format: str | None
+    """The format of the docstring, if specified, e.g. ``restructuredtext`` or ``markdown``."""
+    links: list[DocLink]
+    """The links in the docstring to other items."""
+    sections: DocSections
+    """The docstring, split into its summary, body and sections."""
+    code_blocks: list[CodeBlock]
+    """The fenced code blocks in the docstring, e.g. doctests."""
     fields: list[Field]
 
 class Field:
@@ -187,7 +636,17 @@ class Field:
     """
     path_str: str
     """The fully qualified name as a string, e.g. ``a::b::c``"""
+    span: Span
+    """The location of the field in the source code."""
     docstring: str
+    doc_format: str | None
+    """The format of the docstring, if specified, e.g. ``restructuredtext`` or ``markdown``."""
+    links: list[DocLink]
+    """The links in the docstring to other items."""
+    sections: DocSections
+    """The docstring, split into its summary, body and sections."""
+    code_blocks: list[CodeBlock]
+    """The fenced code blocks in the docstring, e.g. doctests."""
     type_: list[TypeSegment]
 
 class TypeSegment:
@@ -198,6 +657,10 @@ class TypeSegment:
 
     content: str
     is_path: bool
+    target: list[str] | None
+    """The fully qualified path of the analyzed item that the path resolves to."""
+    kind: str | None
+    """The kind of the analyzed item that the path resolves to, e.g. ``struct``."""
 
 class Function:
     """Representation of a function."""
@@ -208,4 +671,31 @@ class Function:
     """The fully qualified path"""
     path_str: str
     """The fully qualified name as a string, e.g. ``a::b::c``"""
+    span: Span
+    """The location of the function in the source code."""
     docstring: str
+    doc_format: str | None
+    """The format of the docstring, if specified, e.g. ``restructuredtext`` or ``markdown``."""
+    links: list[DocLink]
+    """The links in the docstring to other items."""
+    sections: DocSections
+    """The docstring, split into its summary, body and sections."""
+    code_blocks: list[CodeBlock]
+    """The fenced code blocks in the docstring, e.g. doctests."""
+    source: str | None
+    """The source code of the function, as written in the module file."""
+    is_unsafe: bool
+    """Whether the function is declared ``unsafe``."""
+    parameters: list[Parameter]
+    """The parameters of the function."""
+    returns: list[TypeSegment] | None
+    """The return type of the function, if it is not ``()``."""
+    features: list[str]
+    """The features the function is gated on, with ``#[cfg(feature = "...")]``."""
+
+class Parameter:
+    """Representation of a function parameter."""
+
+    name: str
+    """The name of the parameter, or its pattern if it is not a single identifier."""
+    type_: list[TypeSegment]
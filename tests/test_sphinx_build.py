@@ -42,3 +42,201 @@ def test_basic(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
     assert (
         Path(str(app.outdir)).joinpath("api", "crates", "test", "index.html").exists()
     )
+
+
+def write_files(root: Path, files: dict[str, str]) -> None:
+    """Write files to a directory, creating parent directories as needed."""
+    for name, content in files.items():
+        path = root.joinpath(name)
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(dedent(content))
+
+
+def test_workspace(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
+    """Build the documentation of all members of a workspace."""
+    write_files(
+        tmp_path,
+        {
+            "Cargo.toml": """\
+            [workspace]
+            members = ["crates/*"]
+            """,
+            "crates/a/Cargo.toml": """\
+            [package]
+            name = "a"
+            version = "0.1.0"
+            """,
+            "crates/a/src/lib.rs": "//! Crate a.",
+            "crates/b/Cargo.toml": """\
+            [package]
+            name = "b"
+            version = "0.1.0"
+            """,
+            "crates/b/src/lib.rs": "//! Crate b.",
+            "conf.py": """\
+            extensions = ['sphinx_rust']
+            rust_workspaces = ['.']
+            """,
+            "index.rst": """\
+            Test
+            ====
+            .. toctree::
+
+               api/crates/a/index
+               api/crates/b/index
+            """,
+        },
+    )
+
+    app = make_app("html", srcdir=tmp_path)
+    app.build()
+    assert strip_colors(app.warning.getvalue()) == ""  # noqa: PLC1901
+
+    outdir = Path(str(app.outdir))
+    assert outdir.joinpath("api", "crates", "a", "index.html").exists()
+    assert outdir.joinpath("api", "crates", "b", "index.html").exists()
+
+
+def test_root_module(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
+    """Build the documentation of a crate without a Cargo.toml."""
+    write_files(
+        tmp_path,
+        {
+            "src/lib.rs": "//! Test library.",
+            "conf.py": """\
+            extensions = ['sphinx_rust']
+            rust_root_modules = [{'name': 'test', 'path': 'src/lib.rs'}]
+            """,
+            "index.rst": """\
+            Test
+            ====
+            .. toctree:: api/crates/test/index
+            """,
+        },
+    )
+
+    app = make_app("html", srcdir=tmp_path)
+    app.build()
+    assert strip_colors(app.warning.getvalue()) == ""  # noqa: PLC1901
+
+    assert (
+        Path(str(app.outdir)).joinpath("api", "crates", "test", "index.html").exists()
+    )
+
+
+def test_package(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
+    """Build the documentation of a vendored crate."""
+    write_files(
+        tmp_path,
+        {
+            "vendor/test/.cargo-checksum.json": '{"files": {}, "package": null}',
+            "vendor/test/Cargo.toml": """\
+            [package]
+            name = "test"
+            version = "0.1.0"
+            """,
+            "vendor/test/src/lib.rs": "//! Test library.",
+            "conf.py": """\
+            extensions = ['sphinx_rust']
+            rust_packages = ['vendor/test']
+            """,
+            "index.rst": """\
+            Test
+            ====
+            .. toctree:: api/crates/test/index
+            """,
+        },
+    )
+
+    app = make_app("html", srcdir=tmp_path)
+    app.build()
+    assert strip_colors(app.warning.getvalue()) == ""  # noqa: PLC1901
+
+    assert (
+        Path(str(app.outdir)).joinpath("api", "crates", "test", "index.html").exists()
+    )
+
+
+def write_crate(root: Path, lib_rs: str, conf: str = "") -> None:
+    """Write a crate named ``test``, with its documentation at the crate root."""
+    write_files(
+        root,
+        {
+            "Cargo.toml": """\
+            [package]
+            name = "test"
+            version = "0.1.0"
+            """,
+            "src/lib.rs": lib_rs,
+            "conf.py": "extensions = ['sphinx_rust']\nrust_crates = ['.']\n" + conf,
+            "index.rst": """\
+            Test
+            ====
+            """,
+        },
+    )
+
+
+def test_diagnostics(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
+    """Problems found during the analysis are reported at their location."""
+    write_crate(tmp_path, "//! Test library.\npub mod missing;\n")
+
+    app = make_app("html", srcdir=tmp_path)
+    app.build()
+    location = tmp_path.joinpath("src", "lib.rs")
+    assert (
+        f"{location}: WARNING: [rust] file not found for module `test::missing`"
+        in strip_colors(app.warning.getvalue())
+    )
+
+
+def test_invalid_module(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
+    """A module that cannot be parsed is reported as an error, and the rest is documented."""
+    write_crate(tmp_path, "//! Test library.\npub mod broken;\n")
+    write_files(tmp_path, {"src/broken.rs": "pub struct Broken"})
+
+    app = make_app("html", srcdir=tmp_path)
+    app.build()
+    location = tmp_path.joinpath("src", "broken.rs")
+    assert (
+        f"{location}:1: ERROR: [rust] module `test::broken` could not be analyzed"
+        in strip_colors(app.warning.getvalue())
+    )
+    assert tmp_path.joinpath("api", "crates", "test", "index.rst").exists()
+
+
+def test_strict(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
+    """In strict mode, a module that cannot be parsed stops the analysis of the crate."""
+    write_crate(tmp_path, "//! Test library.\npub mod broken;\n", "rust_strict = True")
+    write_files(tmp_path, {"src/broken.rs": "pub struct Broken"})
+
+    app = make_app("html", srcdir=tmp_path)
+    app.build()
+    location = tmp_path.joinpath("src", "broken.rs")
+    assert f"{location}:1: WARNING: Error analyzing crate" in strip_colors(
+        app.warning.getvalue()
+    )
+    assert not tmp_path.joinpath("api", "crates", "test").exists()
+
+
+def test_incremental(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
+    """Files that have not changed since the previous build are not parsed again."""
+    write_crate(tmp_path, "//! Test library.")
+
+    app = make_app("html", srcdir=tmp_path)
+    app.build()
+    assert "[rust] test: 1 files parsed" in strip_colors(app.status.getvalue())
+
+    app = make_app("html", srcdir=tmp_path)
+    app.build()
+    assert "[rust] test: 0 files parsed" in strip_colors(app.status.getvalue())
+
+
+def test_not_incremental(make_app: type[SphinxTestApp], tmp_path: Path) -> None:
+    """With ``rust_incremental = False``, all files are parsed on each build."""
+    write_crate(tmp_path, "//! Test library.", "rust_incremental = False")
+
+    for _ in range(2):
+        app = make_app("html", srcdir=tmp_path)
+        app.build()
+        assert "files parsed" not in strip_colors(app.status.getvalue())